@@ -62,4 +62,197 @@ impl DriverType {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// the max bind params of one sql
+    pub fn max_bind_params(&self) -> usize {
+        match self {
+            DriverType::Mysql => 65535,
+            DriverType::Postgres => 65535,
+            //SQLITE_MAX_VARIABLE_NUMBER default 999 (32766 since sqlite 3.32.0)
+            DriverType::Sqlite => 999,
+            //sqlserver max 2100, one of them used by sp_executesql
+            DriverType::Mssql => 2099,
+            DriverType::None => 0,
+        }
+    }
+}
+
+/// the isolation level of tx
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// the options of begin tx,default is same as begin().
+/// if isolation/read_only/deferrable is set,DBPool::begin_with() exec the begin sql on a plain connection:
+///
+/// mysql: SET TRANSACTION ISOLATION LEVEL .. ; START TRANSACTION [READ ONLY]
+/// postgres: BEGIN ISOLATION LEVEL .., READ ONLY, DEFERRABLE
+/// mssql: SET TRANSACTION ISOLATION LEVEL .. ; BEGIN TRANSACTION. the isolation level is keep by the session,
+///        so reset to READ COMMITTED after commit/rollback. read_only not support
+/// sqlite: the tx is always SERIALIZABLE,so isolation Serializable use BEGIN IMMEDIATE(get the write lock at begin,
+///         avoid SQLITE_BUSY when upgrade lock),other isolation and read_only use BEGIN(DEFERRED).
+///         BEGIN EXCLUSIVE is not need in WAL mode
+/// deferrable: only postgres support
+/// timeout: the tx will be rollback by TxManager if out of time,default(None) is tx_lock_wait_timeout
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub struct TxOptions {
+    pub isolation: Option<IsolationLevel>,
+    pub read_only: bool,
+    pub deferrable: bool,
+    pub timeout: Option<Duration>,
+}
+
+impl TxOptions {
+    pub fn new() -> Self {
+        TxOptions::default()
+    }
+
+    pub fn isolation(mut self, isolation: IsolationLevel) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn deferrable(mut self, deferrable: bool) -> Self {
+        self.deferrable = deferrable;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// is need begin tx by begin_sql(),else use the default begin
+    pub fn is_custom_begin(&self) -> bool {
+        self.isolation.is_some() || self.read_only || self.deferrable
+    }
+
+    /// return the sql(include the BEGIN) need exec on a plain connection to begin tx
+    pub fn begin_sql(&self, driver_type: &DriverType) -> crate::Result<Vec<String>> {
+        if self.deferrable && !driver_type.eq(&DriverType::Postgres) {
+            return Err(crate::Error::from(format!("[rbatis] TxOptions deferrable not support DriverType:{:?}", driver_type)));
+        }
+        let mut sqls = vec![];
+        match driver_type {
+            DriverType::Mysql => {
+                if let Some(isolation) = &self.isolation {
+                    sqls.push(format!("SET TRANSACTION ISOLATION LEVEL {}", isolation.as_sql()));
+                }
+                if self.read_only {
+                    sqls.push("START TRANSACTION READ ONLY".to_string());
+                } else {
+                    sqls.push("START TRANSACTION".to_string());
+                }
+            }
+            DriverType::Postgres => {
+                let mut modes = vec![];
+                if let Some(isolation) = &self.isolation {
+                    modes.push(format!("ISOLATION LEVEL {}", isolation.as_sql()));
+                }
+                if self.read_only {
+                    modes.push("READ ONLY".to_string());
+                }
+                if self.deferrable {
+                    modes.push("DEFERRABLE".to_string());
+                }
+                if modes.is_empty() {
+                    sqls.push("BEGIN".to_string());
+                } else {
+                    sqls.push(format!("BEGIN {}", modes.join(", ")));
+                }
+            }
+            DriverType::Mssql => {
+                if self.read_only {
+                    return Err(crate::Error::from("[rbatis] TxOptions read_only not support DriverType:Mssql"));
+                }
+                if let Some(isolation) = &self.isolation {
+                    sqls.push(format!("SET TRANSACTION ISOLATION LEVEL {}", isolation.as_sql()));
+                }
+                sqls.push("BEGIN TRANSACTION".to_string());
+            }
+            DriverType::Sqlite => {
+                if self.isolation == Some(IsolationLevel::Serializable) && !self.read_only {
+                    sqls.push("BEGIN IMMEDIATE".to_string());
+                } else {
+                    sqls.push("BEGIN".to_string());
+                }
+            }
+            DriverType::None => {
+                return Err(crate::Error::from("un init DBPool!"));
+            }
+        }
+        return Ok(sqls);
+    }
+
+    /// return the sql need exec after commit/rollback,for example mssql reset the isolation level of session
+    pub fn reset_sql(&self, driver_type: &DriverType) -> Option<String> {
+        match driver_type {
+            DriverType::Mssql if self.isolation.is_some() => {
+                Some(format!("SET TRANSACTION ISOLATION LEVEL {}", IsolationLevel::ReadCommitted.as_sql()))
+            }
+            _ => None
+        }
+    }
+}
+
+impl DriverType {
+    /// the commit sql of tx begin by TxOptions::begin_sql()
+    pub fn commit_sql(&self) -> &'static str {
+        match self {
+            DriverType::Mssql => "COMMIT TRANSACTION",
+            _ => "COMMIT"
+        }
+    }
+
+    /// the rollback sql of tx begin by TxOptions::begin_sql()
+    pub fn rollback_sql(&self) -> &'static str {
+        match self {
+            DriverType::Mssql => "ROLLBACK TRANSACTION",
+            _ => "ROLLBACK"
+        }
+    }
+}
+
+#[test]
+fn test_tx_options_begin_sql() {
+    let options = TxOptions::new().isolation(IsolationLevel::Serializable).read_only(true);
+    assert!(options.is_custom_begin());
+    assert_eq!(options.begin_sql(&DriverType::Mysql).unwrap(),
+               vec!["SET TRANSACTION ISOLATION LEVEL SERIALIZABLE", "START TRANSACTION READ ONLY"]);
+    assert_eq!(options.deferrable(true).begin_sql(&DriverType::Postgres).unwrap(),
+               vec!["BEGIN ISOLATION LEVEL SERIALIZABLE, READ ONLY, DEFERRABLE"]);
+    assert!(options.begin_sql(&DriverType::Mssql).is_err());
+    assert!(options.deferrable(true).begin_sql(&DriverType::Mysql).is_err());
+    assert_eq!(TxOptions::new().isolation(IsolationLevel::Serializable).begin_sql(&DriverType::Sqlite).unwrap(),
+               vec!["BEGIN IMMEDIATE"]);
+    assert_eq!(TxOptions::new().read_only(true).begin_sql(&DriverType::Sqlite).unwrap(), vec!["BEGIN"]);
+
+    let options = TxOptions::new().isolation(IsolationLevel::RepeatableRead);
+    assert_eq!(options.begin_sql(&DriverType::Mssql).unwrap(),
+               vec!["SET TRANSACTION ISOLATION LEVEL REPEATABLE READ", "BEGIN TRANSACTION"]);
+    assert_eq!(options.reset_sql(&DriverType::Mssql).unwrap(), "SET TRANSACTION ISOLATION LEVEL READ COMMITTED");
+    assert!(options.reset_sql(&DriverType::Postgres).is_none());
+    let options = TxOptions::new().timeout(Duration::from_secs(1));
+    assert_eq!(options.timeout, Some(Duration::from_secs(1)));
+    assert!(!options.is_custom_begin());
+}
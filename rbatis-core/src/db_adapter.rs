@@ -13,20 +13,20 @@ use sqlx_core::done::Done;
 use sqlx_core::encode::Encode;
 use sqlx_core::executor::Executor;
 #[cfg(feature = "mssql")]
-use sqlx_core::mssql::{Mssql, MssqlArguments, MssqlConnection, MssqlConnectOptions, MssqlDone, MssqlPool, MssqlRow};
+use sqlx_core::mssql::{Mssql, MssqlArguments, MssqlConnection, MssqlConnectOptions, MssqlDatabaseError, MssqlDone, MssqlPool, MssqlRow};
 #[cfg(feature = "mysql")]
-use sqlx_core::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlConnectOptions, MySqlDone, MySqlPool, MySqlRow};
+use sqlx_core::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlConnectOptions, MySqlDatabaseError, MySqlDone, MySqlPool, MySqlRow};
 use sqlx_core::pool::PoolConnection;
 #[cfg(feature = "postgres")]
-use sqlx_core::postgres::{PgArguments, PgConnection, PgConnectOptions, PgDone, PgPool, PgPoolOptions, PgRow, Postgres};
+use sqlx_core::postgres::{PgArguments, PgConnection, PgConnectOptions, PgDatabaseError, PgDone, PgPool, PgPoolOptions, PgRow, Postgres};
 use sqlx_core::query::{Query, query};
 #[cfg(feature = "sqlite")]
-use sqlx_core::sqlite::{Sqlite, SqliteArguments, SqliteConnection, SqliteConnectOptions, SqliteDone, SqlitePool, SqliteRow};
+use sqlx_core::sqlite::{Sqlite, SqliteArguments, SqliteConnection, SqliteConnectOptions, SqliteDone, SqliteError, SqlitePool, SqliteRow};
 use sqlx_core::transaction::Transaction;
 use sqlx_core::types::Type;
 
 use crate::convert::RefJsonCodec;
-use crate::db::{DriverType, PoolOptions};
+use crate::db::{DriverType, PoolOptions, TxOptions};
 use crate::decode::json_decode;
 use crate::Error;
 use crate::runtime::Mutex;
@@ -64,7 +64,7 @@ impl DBPool {
                 {
                     let opt = MySqlConnectOptions::from_str(driver);
                     if opt.is_err() {
-                        return Err(Error::from(format!("{:?}", opt.err().unwrap())));
+                        return Err(convert_error(opt.err().unwrap()));
                     }
                     let mut opt = opt.unwrap();
                     opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -72,7 +72,7 @@ impl DBPool {
                     pool.driver_type = DriverType::Mysql;
                     let conn = MySqlPool::connect_with(opt).await;
                     if conn.is_err() {
-                        return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                        return Err(convert_error(conn.err().unwrap()));
                     }
                     pool.mysql = Some(conn.unwrap());
                 }
@@ -85,7 +85,7 @@ impl DBPool {
                 {
                     let opt = PgConnectOptions::from_str(driver);
                     if opt.is_err() {
-                        return Err(Error::from(format!("{:?}", opt.err().unwrap())));
+                        return Err(convert_error(opt.err().unwrap()));
                     }
                     let mut opt = opt.unwrap();
                     opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -93,7 +93,7 @@ impl DBPool {
                     pool.driver_type = DriverType::Postgres;
                     let conn = PgPool::connect_with(opt).await;
                     if conn.is_err() {
-                        return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                        return Err(convert_error(conn.err().unwrap()));
                     }
                     pool.postgres = Some(conn.unwrap());
                 }
@@ -106,7 +106,7 @@ impl DBPool {
                 {
                     let opt = SqliteConnectOptions::from_str(driver);
                     if opt.is_err() {
-                        return Err(Error::from(format!("{:?}", opt.err().unwrap())));
+                        return Err(convert_error(opt.err().unwrap()));
                     }
                     let mut opt = opt.unwrap();
                     opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -114,7 +114,7 @@ impl DBPool {
                     pool.driver_type = DriverType::Sqlite;
                     let conn = SqlitePool::connect_with(opt).await;
                     if conn.is_err() {
-                        return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                        return Err(convert_error(conn.err().unwrap()));
                     }
                     pool.sqlite = Some(conn.unwrap());
                 }
@@ -127,7 +127,7 @@ impl DBPool {
                 {
                     let opt = MssqlConnectOptions::from_str(driver);
                     if opt.is_err() {
-                        return Err(Error::from(format!("{:?}", opt.err().unwrap())));
+                        return Err(convert_error(opt.err().unwrap()));
                     }
                     let mut opt = opt.unwrap();
                     opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -135,7 +135,7 @@ impl DBPool {
                     pool.driver_type = DriverType::Mssql;
                     let conn = MssqlPool::connect_with(opt).await;
                     if conn.is_err() {
-                        return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                        return Err(convert_error(conn.err().unwrap()));
                     }
                     pool.mssql = Some(conn.unwrap());
                 }
@@ -167,7 +167,7 @@ impl DBPool {
                 {
                     let conn_opt = MySqlConnectOptions::from_str(driver);
                     if conn_opt.is_err() {
-                        return Err(Error::from(format!("{:?}", conn_opt.err().unwrap())));
+                        return Err(convert_error(conn_opt.err().unwrap()));
                     }
                     let mut conn_opt = conn_opt.unwrap();
                     conn_opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -183,7 +183,7 @@ impl DBPool {
                         .test_before_acquire(opt.test_before_acquire);
                     let p = build.connect_with(conn_opt).await;
                     if p.is_err() {
-                        return Err(crate::Error::from(p.err().unwrap().to_string()));
+                        return Err(convert_error(p.err().unwrap()));
                     }
                     pool.mysql = Some(p.unwrap());
                 }
@@ -196,7 +196,7 @@ impl DBPool {
                 {
                     let conn_opt = PgConnectOptions::from_str(driver);
                     if conn_opt.is_err() {
-                        return Err(Error::from(format!("{:?}", conn_opt.err().unwrap())));
+                        return Err(convert_error(conn_opt.err().unwrap()));
                     }
                     let mut conn_opt = conn_opt.unwrap();
                     conn_opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -211,7 +211,7 @@ impl DBPool {
                         .test_before_acquire(opt.test_before_acquire);
                     let p = build.connect_with(conn_opt).await;
                     if p.is_err() {
-                        return Err(crate::Error::from(p.err().unwrap().to_string()));
+                        return Err(convert_error(p.err().unwrap()));
                     }
                     pool.postgres = Some(p.unwrap());
                 }
@@ -224,7 +224,7 @@ impl DBPool {
                 {
                     let conn_opt = SqliteConnectOptions::from_str(driver);
                     if conn_opt.is_err() {
-                        return Err(Error::from(format!("{:?}", conn_opt.err().unwrap())));
+                        return Err(convert_error(conn_opt.err().unwrap()));
                     }
                     let mut conn_opt = conn_opt.unwrap();
                     conn_opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -239,7 +239,7 @@ impl DBPool {
                         .test_before_acquire(opt.test_before_acquire);
                     let p = build.connect_with(conn_opt).await;
                     if p.is_err() {
-                        return Err(crate::Error::from(p.err().unwrap().to_string()));
+                        return Err(convert_error(p.err().unwrap()));
                     }
                     pool.sqlite = Some(p.unwrap());
                 }
@@ -252,7 +252,7 @@ impl DBPool {
                 {
                     let conn_opt = MssqlConnectOptions::from_str(driver);
                     if conn_opt.is_err() {
-                        return Err(Error::from(format!("{:?}", conn_opt.err().unwrap())));
+                        return Err(convert_error(conn_opt.err().unwrap()));
                     }
                     let mut conn_opt = conn_opt.unwrap();
                     conn_opt.log_slow_statements(log::LevelFilter::Off, Duration::from_secs(0));
@@ -267,7 +267,7 @@ impl DBPool {
                         .test_before_acquire(opt.test_before_acquire);
                     let p = build.connect_with(conn_opt).await;
                     if p.is_err() {
-                        return Err(crate::Error::from(p.err().unwrap().to_string()));
+                        return Err(convert_error(p.err().unwrap()));
                     }
                     pool.mssql = Some(p.unwrap());
                 }
@@ -352,7 +352,7 @@ impl DBPool {
             &DriverType::Mysql => {
                 let conn = self.mysql.as_ref().unwrap().acquire().await;
                 if conn.is_err() {
-                    return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                    return Err(convert_error(conn.err().unwrap()));
                 }
                 return Ok(DBPoolConn {
                     driver_type: DriverType::Mysql,
@@ -370,7 +370,7 @@ impl DBPool {
             &DriverType::Postgres => {
                 let conn = self.postgres.as_ref().unwrap().acquire().await;
                 if conn.is_err() {
-                    return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                    return Err(convert_error(conn.err().unwrap()));
                 }
                 return Ok(DBPoolConn {
                     driver_type: DriverType::Postgres,
@@ -388,7 +388,7 @@ impl DBPool {
             &DriverType::Sqlite => {
                 let conn = self.sqlite.as_ref().unwrap().acquire().await;
                 if conn.is_err() {
-                    return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                    return Err(convert_error(conn.err().unwrap()));
                 }
                 return Ok(DBPoolConn {
                     driver_type: DriverType::Sqlite,
@@ -406,7 +406,7 @@ impl DBPool {
             &DriverType::Mssql => {
                 let conn = self.mssql.as_ref().unwrap().acquire().await;
                 if conn.is_err() {
-                    return Err(crate::Error::from(conn.err().unwrap().to_string()));
+                    return Err(convert_error(conn.err().unwrap()));
                 }
                 return Ok(DBPoolConn {
                     driver_type: DriverType::Mssql,
@@ -530,6 +530,8 @@ impl DBPool {
                     sqlite: None,
                     #[cfg(feature = "mssql")]
                     mssql: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
             &DriverType::Postgres => {
@@ -543,6 +545,8 @@ impl DBPool {
                     sqlite: None,
                     #[cfg(feature = "mssql")]
                     mssql: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
             &DriverType::Sqlite => {
@@ -556,6 +560,8 @@ impl DBPool {
                     mysql: None,
                     #[cfg(feature = "mssql")]
                     mssql: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
             &DriverType::Mssql => {
@@ -569,11 +575,46 @@ impl DBPool {
                     postgres: None,
                     #[cfg(feature = "sqlite")]
                     sqlite: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
         }
     }
 
+    /// begin tx with options,for example isolation level,read only.
+    /// the begin sql is exec on a plain connection,then the connection is wrap as DBTx
+    pub async fn begin_with(&self, options: &TxOptions) -> crate::Result<DBTx> {
+        if !options.is_custom_begin() {
+            return self.begin().await;
+        }
+        let sqls = options.begin_sql(&self.driver_type)?;
+        let reset_sql = options.reset_sql(&self.driver_type);
+        let mut conn = self.acquire().await?;
+        for sql in &sqls {
+            if let Err(e) = conn.execute(sql).await {
+                if let Some(reset_sql) = &reset_sql {
+                    let _ = conn.execute(reset_sql).await;
+                }
+                conn.close().await?;
+                return Err(e);
+            }
+        }
+        Ok(DBTx {
+            driver_type: self.driver_type,
+            #[cfg(feature = "mysql")]
+            mysql: None,
+            #[cfg(feature = "postgres")]
+            postgres: None,
+            #[cfg(feature = "sqlite")]
+            sqlite: None,
+            #[cfg(feature = "mssql")]
+            mssql: None,
+            conn: Some(conn),
+            reset_sql: reset_sql,
+        })
+    }
+
     pub async fn close(&self) {
         match &self.driver_type {
             &DriverType::None => {
@@ -1038,6 +1079,8 @@ impl DBPoolConn {
                     sqlite: None,
                     #[cfg(feature = "mssql")]
                     mssql: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
             &DriverType::Postgres => {
@@ -1051,6 +1094,8 @@ impl DBPoolConn {
                     sqlite: None,
                     #[cfg(feature = "mssql")]
                     mssql: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
             &DriverType::Sqlite => {
@@ -1064,6 +1109,8 @@ impl DBPoolConn {
                     mysql: None,
                     #[cfg(feature = "mssql")]
                     mssql: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
             &DriverType::Mssql => {
@@ -1077,6 +1124,8 @@ impl DBPoolConn {
                     sqlite: None,
                     #[cfg(feature = "postgres")]
                     postgres: None,
+                    conn: None,
+                    reset_sql: None,
                 })
             }
         }
@@ -1153,11 +1202,18 @@ pub struct DBTx {
     pub sqlite: Option<Mutex<Transaction<'static, Sqlite>>>,
     #[cfg(feature = "mssql")]
     pub mssql: Option<Transaction<'static, Mssql>>,
+    /// the plain connection of tx begin by DBPool::begin_with()
+    pub conn: Option<DBPoolConn>,
+    /// the sql exec on conn after commit/rollback
+    pub reset_sql: Option<String>,
 }
 
 
 impl DBTx {
     pub async fn commit(&mut self) -> crate::Result<()> {
+        if let Some(conn) = self.conn.take() {
+            return end_conn_tx(conn, self.driver_type.commit_sql(), self.reset_sql.take()).await;
+        }
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
@@ -1185,6 +1241,9 @@ impl DBTx {
     }
 
     pub async fn rollback(mut self) -> crate::Result<()> {
+        if let Some(conn) = self.conn.take() {
+            return end_conn_tx(conn, self.driver_type.rollback_sql(), self.reset_sql.take()).await;
+        }
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
@@ -1213,6 +1272,9 @@ impl DBTx {
 
     pub async fn fetch<'q, T>(&mut self, sql: &'q str) -> crate::Result<(T, usize)>
         where T: DeserializeOwned {
+        if let Some(conn) = self.conn.as_mut() {
+            return conn.fetch(sql).await;
+        }
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
@@ -1257,6 +1319,9 @@ impl DBTx {
 
     pub async fn fetch_parperd<'q, T>(&mut self, sql: DBQuery<'q>) -> crate::Result<(T, usize)>
         where T: DeserializeOwned {
+        if let Some(conn) = self.conn.as_mut() {
+            return conn.fetch_parperd(sql).await;
+        }
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
@@ -1300,6 +1365,9 @@ impl DBTx {
     }
 
     pub async fn execute(&mut self, sql: &str) -> crate::Result<DBExecResult> {
+        if let Some(conn) = self.conn.as_mut() {
+            return conn.execute(sql).await;
+        }
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
@@ -1332,6 +1400,9 @@ impl DBTx {
 
 
     pub async fn exec_prepare(&mut self, sql: DBQuery<'_>) -> crate::Result<DBExecResult> {
+        if let Some(conn) = self.conn.as_mut() {
+            return conn.exec_prepare(sql).await;
+        }
         match &self.driver_type {
             &DriverType::None => {
                 return Err(Error::from("un init DBPool!"));
@@ -1363,13 +1434,128 @@ impl DBTx {
     }
 }
 
+impl Drop for DBTx {
+    fn drop(&mut self) {
+        //the tx begin by begin_with() not commit/rollback,rollback it before the connection back to pool
+        if let Some(conn) = self.conn.take() {
+            let rollback_sql = self.driver_type.rollback_sql();
+            let reset_sql = self.reset_sql.take();
+            crate::runtime::spawn(async move {
+                let _ = end_conn_tx(conn, rollback_sql, reset_sql).await;
+            });
+        }
+    }
+}
+
+/// commit/rollback the tx on a plain connection,then exec the reset sql.
+/// if commit fail,rollback it,so the connection back to pool not in tx
+async fn end_conn_tx(mut conn: DBPoolConn, end_sql: &str, reset_sql: Option<String>) -> crate::Result<()> {
+    let mut result = conn.execute(end_sql).await.map(|_| ());
+    if result.is_err() && end_sql != conn.driver_type.rollback_sql() {
+        let _ = conn.execute(conn.driver_type.rollback_sql()).await;
+    }
+    if let Some(reset_sql) = reset_sql {
+        let reset_result = conn.execute(&reset_sql).await.map(|_| ());
+        if result.is_ok() {
+            result = reset_result;
+        }
+    }
+    conn.close().await?;
+    return result;
+}
+
 pub fn convert_result<T>(arg: Result<T, sqlx_core::error::Error>) -> crate::Result<T> {
     if arg.is_err() {
-        return Err(crate::Error::from(arg.err().unwrap().to_string()));
+        return Err(convert_error(arg.err().unwrap()));
     }
     return Ok(arg.unwrap());
 }
 
+/// convert sqlx error to the Error variant
+pub fn convert_error(arg: sqlx_core::error::Error) -> crate::Error {
+    match arg {
+        sqlx_core::error::Error::Database(e) => {
+            crate::Error::Database {
+                driver_type: database_error_driver(e.as_ref()),
+                code: database_error_code(e.as_ref()),
+                constraint: database_error_constraint(e.as_ref()),
+                message: e.message().to_string(),
+            }
+        }
+        sqlx_core::error::Error::RowNotFound => crate::Error::NotFound,
+        sqlx_core::error::Error::PoolTimedOut => crate::Error::PoolTimeout,
+        sqlx_core::error::Error::Configuration(e) => crate::Error::Config(e.to_string()),
+        e @ sqlx_core::error::Error::Decode(_) |
+        e @ sqlx_core::error::Error::ColumnDecode { .. } => crate::Error::Decode(e.to_string()),
+        e @ sqlx_core::error::Error::Io(_) |
+        e @ sqlx_core::error::Error::Tls(_) |
+        e @ sqlx_core::error::Error::Protocol(_) => crate::Error::Protocol(e.to_string()),
+        e => crate::Error::from(e.to_string())
+    }
+}
+
+#[test]
+fn test_convert_error() {
+    assert!(convert_error(sqlx_core::error::Error::PoolTimedOut).is_pool_timeout());
+    assert!(convert_error(sqlx_core::error::Error::RowNotFound).is_not_found());
+    match convert_error(sqlx_core::error::Error::Configuration("bad url".into())) {
+        crate::Error::Config(_) => {}
+        e => panic!("expect Config,but:{:?}", e)
+    }
+}
+
+/// find the driver of database error
+fn database_error_driver(e: &dyn sqlx_core::error::DatabaseError) -> DriverType {
+    #[cfg(feature = "mysql")]
+    {
+        if e.try_downcast_ref::<MySqlDatabaseError>().is_some() {
+            return DriverType::Mysql;
+        }
+    }
+    #[cfg(feature = "postgres")]
+    {
+        if e.try_downcast_ref::<PgDatabaseError>().is_some() {
+            return DriverType::Postgres;
+        }
+    }
+    #[cfg(feature = "sqlite")]
+    {
+        if e.try_downcast_ref::<SqliteError>().is_some() {
+            return DriverType::Sqlite;
+        }
+    }
+    #[cfg(feature = "mssql")]
+    {
+        if e.try_downcast_ref::<MssqlDatabaseError>().is_some() {
+            return DriverType::Mssql;
+        }
+    }
+    return DriverType::None;
+}
+
+/// the code of database error,mysql SQLSTATE 23000 is too wide,use the error number
+fn database_error_code(e: &dyn sqlx_core::error::DatabaseError) -> Option<String> {
+    #[cfg(feature = "mysql")]
+    {
+        if let Some(mysql_err) = e.try_downcast_ref::<MySqlDatabaseError>() {
+            return Some(mysql_err.number().to_string());
+        }
+    }
+    return e.code().map(|code| code.to_string());
+}
+
+/// the violated constraint name of database error,postgres only
+#[allow(unused_variables)]
+fn database_error_constraint(e: &dyn sqlx_core::error::DatabaseError) -> Option<String> {
+    #[cfg(feature = "postgres")]
+    {
+        if let Some(pg_err) = e.try_downcast_ref::<PgDatabaseError>() {
+            return pg_err.constraint().map(|c| c.to_string());
+        }
+    }
+    return None;
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct DBExecResult {
     pub rows_affected: u64,
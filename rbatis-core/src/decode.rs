@@ -11,6 +11,7 @@ pub fn json_decode<T: ?Sized>(datas: Vec<serde_json::Value>) -> Result<T, crate:
     where T: DeserializeOwned {
     let mut js = serde_json::Value::Null;
     let type_name = std::any::type_name::<T>();
+    let rows_empty = datas.is_empty();
     if is_array(type_name) {
         //decode array
         js = serde_json::Value::Array(datas);
@@ -77,8 +78,12 @@ pub fn json_decode<T: ?Sized>(datas: Vec<serde_json::Value>) -> Result<T, crate:
     if decode_result.is_ok() {
         return Result::Ok(decode_result.unwrap());
     } else {
+        if rows_empty && !is_array(type_name) {
+            //expect one row but no row
+            return Result::Err(Error::NotFound);
+        }
         let e = decode_result.err().unwrap().to_string();
-        return Result::Err(Error::from(format!("[rbatis] json_decode fail decode_type:  {},serde_err: {}", type_name, e)));
+        return Result::Err(Error::Decode(format!("[rbatis] json_decode fail decode_type:  {},serde_err: {}", type_name, e)));
     }
 }
 
@@ -128,4 +133,12 @@ mod test {
         )]).unwrap();
         println!("{:#?}", m);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_decode_not_found() {
+        let r: Result<HashMap<String, serde_json::Value>, crate::Error> = json_decode(vec![]);
+        assert!(r.err().unwrap().is_not_found());
+        let r: Option<HashMap<String, serde_json::Value>> = json_decode(vec![]).unwrap();
+        assert!(r.is_none());
+    }
+}
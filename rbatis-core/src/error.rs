@@ -4,15 +4,137 @@ use std::error::Error as StdError;
 use std::fmt::{self, Debug, Display};
 use std::io;
 
+use crate::db::DriverType;
+
 /// A specialized `Result` type for rbatis::core.
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// A generic error that represents all the ways a method can fail inside of rbatis::core.
-#[derive(Debug)]
+/// the error serialize as string,and deserialize to Error::E
+#[derive(Debug, Clone)]
 #[non_exhaustive]
 pub enum Error {
     /// Default Error
     E(String),
+    /// the error returned from the database.
+    /// code: postgres is the SQLSTATE,sqlite is the extended result code,mysql is the error number(for example "1062").
+    /// the code is match by driver_type,because the same code means different error on different database
+    Database {
+        driver_type: DriverType,
+        code: Option<String>,
+        constraint: Option<String>,
+        message: String,
+    },
+    /// acquire connection from pool out of time
+    PoolTimeout,
+    /// decode row fail
+    Decode(String),
+    /// no row returned but expect one
+    NotFound,
+    /// the connection(io/tls/protocol) error,for example connection lost
+    Protocol(String),
+    /// the config of database(for example the url) is wrong
+    Config(String),
+    /// optimistic lock fail,the row of version is changed or not exist
+    VersionLock {
+        table: String,
+        column: String,
+        version: serde_json::Value,
+    },
+}
+
+impl Error {
+    /// the code of Database error
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Error::Database { code, .. } => code.as_deref(),
+            _ => None
+        }
+    }
+
+    /// the violated constraint name of Database error(postgres only)
+    pub fn constraint(&self) -> Option<&str> {
+        match self {
+            Error::Database { constraint, .. } => constraint.as_deref(),
+            _ => None
+        }
+    }
+
+    /// is serialization failure or deadlock,can retry
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Database { driver_type, code: Some(code), .. } => is_retryable_code(driver_type, code),
+            _ => false
+        }
+    }
+
+    /// postgres 23505,mysql 1062,sqlite 2067/1555
+    pub fn is_unique_violation(&self) -> bool {
+        match self {
+            Error::Database { driver_type, code: Some(code), .. } => {
+                match (driver_type, code.as_str()) {
+                    (DriverType::Postgres, "23505") |
+                    (DriverType::Mysql, "1062") |
+                    (DriverType::Sqlite, "2067") | (DriverType::Sqlite, "1555") => true,
+                    _ => false
+                }
+            }
+            _ => false
+        }
+    }
+
+    /// postgres 23503,mysql 1451/1452,sqlite 787
+    pub fn is_foreign_key_violation(&self) -> bool {
+        match self {
+            Error::Database { driver_type, code: Some(code), .. } => {
+                match (driver_type, code.as_str()) {
+                    (DriverType::Postgres, "23503") |
+                    (DriverType::Mysql, "1451") | (DriverType::Mysql, "1452") |
+                    (DriverType::Sqlite, "787") => true,
+                    _ => false
+                }
+            }
+            _ => false
+        }
+    }
+
+    pub fn is_version_lock(&self) -> bool {
+        match self {
+            Error::VersionLock { .. } => true,
+            _ => false
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Error::NotFound => true,
+            _ => false
+        }
+    }
+
+    pub fn is_pool_timeout(&self) -> bool {
+        match self {
+            Error::PoolTimeout => true,
+            _ => false
+        }
+    }
+
+    pub fn is_protocol(&self) -> bool {
+        match self {
+            Error::Protocol(_) => true,
+            _ => false
+        }
+    }
+}
+
+/// postgres 40001: serialization_failure,40P01: deadlock_detected. mysql 1213: deadlock(mysql 1205 is lock wait timeout,not retry).
+/// mssql error number is not returned by the driver,so never retry
+pub fn is_retryable_code(driver_type: &DriverType, code: &str) -> bool {
+    match (driver_type, code) {
+        (DriverType::Postgres, "40001") | (DriverType::Postgres, "40P01") |
+        (DriverType::Mysql, "1213") => true,
+        _ => false
+    }
 }
 
 impl Display for Error {
@@ -21,6 +143,20 @@ impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::E(error) => write!(f, "{}", error),
+            Error::Database { code, message, .. } => {
+                match code {
+                    Some(code) => write!(f, "error returned from database: ({}) {}", code, message),
+                    None => write!(f, "error returned from database: {}", message),
+                }
+            }
+            Error::PoolTimeout => write!(f, "pool timed out while waiting for an open connection"),
+            Error::Decode(error) => write!(f, "{}", error),
+            Error::NotFound => write!(f, "no rows returned by a query that expected to return at least one row"),
+            Error::Protocol(error) => write!(f, "{}", error),
+            Error::Config(error) => write!(f, "error with configuration: {}", error),
+            Error::VersionLock { table, column, version } => {
+                write!(f, "[rbatis] version lock fail! table:{} {} = {} is changed or not exist!", table, column, version)
+            }
         }
     }
 }
@@ -52,16 +188,6 @@ impl From<&dyn std::error::Error> for Error {
     }
 }
 
-impl Clone for Error {
-    fn clone(&self) -> Self {
-        Error::from(self.to_string())
-    }
-
-    fn clone_from(&mut self, source: &Self) {
-        *self = Self::from(source.to_string());
-    }
-}
-
 
 use serde::ser::{Serialize, Serializer};
 use serde::{Deserialize, Deserializer};
@@ -113,6 +239,31 @@ impl<'de> Deserialize<'de> for Error {
 }
 
 
+#[test]
+fn test_retryable() {
+    assert!(is_retryable_code(&DriverType::Postgres, "40001"));
+    assert!(is_retryable_code(&DriverType::Postgres, "40P01"));
+    assert!(!is_retryable_code(&DriverType::Postgres, "23505"));
+    assert!(!is_retryable_code(&DriverType::Mysql, "1205"));
+    assert!(!is_retryable_code(&DriverType::Mssql, "1205"));
+    let e = Error::Database { driver_type: DriverType::Mysql, code: Some("1213".to_string()), constraint: None, message: "Deadlock found".to_string() };
+    assert!(e.clone().is_retryable());
+    assert!(!Error::from("deadlock").is_retryable());
+}
+
+#[test]
+fn test_database_error() {
+    let e = Error::Database { driver_type: DriverType::Postgres, code: Some("23505".to_string()), constraint: Some("biz_activity_pkey".to_string()), message: "duplicate key".to_string() };
+    assert!(e.is_unique_violation());
+    let mysql_e = Error::Database { driver_type: DriverType::Mysql, code: Some("23505".to_string()), constraint: None, message: "".to_string() };
+    assert!(!mysql_e.is_unique_violation());
+    assert!(!e.is_foreign_key_violation());
+    assert_eq!(e.constraint(), Some("biz_activity_pkey"));
+    assert_eq!(serde_json::to_string(&e).unwrap(), "\"error returned from database: (23505) duplicate key\"");
+    assert!(Error::NotFound.is_not_found());
+    assert!(!e.is_version_lock());
+}
+
 #[test]
 fn test_json_error(){
     let e=Error::from("fuck");
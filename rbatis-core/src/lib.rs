@@ -25,6 +25,7 @@ extern crate lazy_static;
 pub mod decode;
 
 pub use error::{Error, Result};
+pub use db::{IsolationLevel, TxOptions};
 
 pub mod db_adapter;
 
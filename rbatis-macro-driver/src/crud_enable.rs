@@ -9,7 +9,7 @@ use syn::ext::IdentExt;
 use crate::proc_macro::TokenStream;
 
 ///impl CRUDEnable
-pub(crate) fn impl_crud_driver(ast: &syn::DeriveInput, arg_id_type: &str, arg_id_name: &str, arg_table_name: &str, arg_table_columns: &str) -> TokenStream {
+pub(crate) fn impl_crud_driver(ast: &syn::DeriveInput, arg_id_type: &str, arg_id_name: &str, arg_table_name: &str, arg_table_columns: &str, arg_id_strategy: &str) -> TokenStream {
     let name = &ast.ident;
     let id_name;
     if arg_id_name.is_empty() {
@@ -36,8 +36,23 @@ pub(crate) fn impl_crud_driver(ast: &syn::DeriveInput, arg_id_type: &str, arg_id
     } else {
         fields = quote! {#arg_table_columns.to_string()};
     }
+    let id_strategy;
+    if arg_id_strategy.is_empty() {
+        id_strategy = quote! {};
+    } else {
+        let strategy = gen_id_strategy(arg_id_strategy);
+        id_strategy = quote! {
+            fn id_strategy() -> rbatis::crud::IdStrategy {
+                 rbatis::crud::IdStrategy::#strategy
+            }
+        };
+    }
+
+    let columns = gen_columns(ast, arg_table_columns);
 
     let gen = quote! {
+        #columns
+
         impl CRUDEnable for #name {
             type IdType = #id_type;
 
@@ -52,11 +67,27 @@ pub(crate) fn impl_crud_driver(ast: &syn::DeriveInput, arg_id_type: &str, arg_id
             fn table_columns() -> String{
                  #fields
             }
+
+            #id_strategy
         }
     };
     gen.into()
 }
 
+///id_strategy:snowflake|uuid|auto_increment|input
+fn gen_id_strategy(arg: &str) -> Ident {
+    let strategy = match arg.trim_matches('"') {
+        "snowflake" => "Snowflake",
+        "uuid" => "Uuid",
+        "auto_increment" => "AutoIncrement",
+        "input" => "Input",
+        _ => {
+            panic!("[rbaits] crud_enable id_strategy must be one of snowflake,uuid,auto_increment,input");
+        }
+    };
+    Ident::new(strategy, Span::call_site())
+}
+
 fn gen_table_name(data: &syn::Ident) -> String {
     let mut table_name = data.to_string();
     let names: Vec<&str> = table_name.split("::").collect();
@@ -71,7 +102,7 @@ fn gen_fields(data: &syn::Data) -> proc_macro2::TokenStream {
         syn::Data::Struct(s) => {
             let mut index = 0;
             for field in &s.fields {
-                let field_name = &field.ident.as_ref().map(|ele| ele.unraw()).to_token_stream().to_string();
+                let field_name = &field_column_name(field);
                 if index == 0 {
                     fields = fields + field_name
                 } else {
@@ -87,6 +118,104 @@ fn gen_fields(data: &syn::Data) -> proc_macro2::TokenStream {
     fields.to_token_stream()
 }
 
+/// the column name of field: #[serde(rename = "..")] or the field name
+fn field_column_name(field: &syn::Field) -> String {
+    for attr in &field.attrs {
+        if !attr.path.is_ident("serde") {
+            continue;
+        }
+        if let Ok(syn::Meta::List(list)) = attr.parse_meta() {
+            for nested in &list.nested {
+                match nested {
+                    // #[serde(rename = "name")]
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) if nv.path.is_ident("rename") => {
+                        if let syn::Lit::Str(lit) = &nv.lit {
+                            return lit.value();
+                        }
+                    }
+                    // #[serde(rename(serialize = "name"))]
+                    syn::NestedMeta::Meta(syn::Meta::List(rename)) if rename.path.is_ident("rename") => {
+                        for item in &rename.nested {
+                            if let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = item {
+                                if let (true, syn::Lit::Str(lit)) = (nv.path.is_ident("serialize"), &nv.lit) {
+                                    return lit.value();
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    field.ident.as_ref().map(|ele| ele.unraw()).to_token_stream().to_string()
+}
+
+/// gen the typed columns of struct,the column name is same as table_columns(), for example:
+///  pub struct BizActivityColumns { pub id: rbatis::wrapper::Column<String>, ... }
+///  impl BizActivity { pub const COLUMNS: BizActivityColumns = ...; }
+/// if set table_columns config,only the field in table_columns will be generated
+fn gen_columns(ast: &syn::DeriveInput, arg_table_columns: &str) -> proc_macro2::TokenStream {
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let fields = match &ast.data {
+        syn::Data::Struct(data_struct) => match &data_struct.fields {
+            syn::Fields::Named(fields_named) => &fields_named.named,
+            _ => {
+                return quote! {};
+            }
+        },
+        _ => {
+            return quote! {};
+        }
+    };
+    let table_columns: Vec<String> = arg_table_columns.trim_matches('"').split(",")
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    let columns_name = Ident::new(&format!("{}Columns", name), Span::call_site());
+    let mut defines = vec![];
+    let mut values = vec![];
+    for field in fields {
+        let field_ident = field.ident.as_ref().unwrap();
+        let column = field_column_name(field);
+        if !table_columns.is_empty() && !table_columns.contains(&column) {
+            continue;
+        }
+        let ty = find_option_inner_type(&field.ty);
+        defines.push(quote! { pub #field_ident: rbatis::wrapper::Column<#ty> });
+        values.push(quote! { #field_ident: rbatis::wrapper::Column::new(#column) });
+    }
+    quote! {
+        #[derive(Clone, Copy, Debug)]
+        #vis struct #columns_name {
+            #(#defines),*
+        }
+
+        impl #name {
+            /// the typed columns of table
+            #vis const COLUMNS: #columns_name = #columns_name {
+                #(#values),*
+            };
+        }
+    }
+}
+
+/// Option<T> => T, other type return itself
+fn find_option_inner_type(ty: &syn::Type) -> &syn::Type {
+    if let syn::Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                        return inner;
+                    }
+                }
+            }
+        }
+    }
+    ty
+}
 
 ///filter id_type
 fn find_id_type_ident(arg: &syn::Data) -> Ident {
@@ -144,6 +273,7 @@ pub struct CrudEnableConfig {
     pub id_type: String,
     pub table_name: String,
     pub table_columns: String,
+    pub id_strategy: String,
 }
 
 /// impl the crud macro
@@ -152,7 +282,7 @@ pub(crate) fn impl_crud(args: TokenStream, input: TokenStream) -> TokenStream {
     let config = read_config(&arg_str);
     let input_clone: proc_macro2::TokenStream = input.clone().into();
     let ast = syn::parse(input).unwrap();
-    let stream = impl_crud_driver(&ast, &config.id_type, &config.id_name, &config.table_name, &config.table_columns);
+    let stream = impl_crud_driver(&ast, &config.id_type, &config.id_name, &config.table_name, &config.table_columns, &config.id_strategy);
     let s: proc_macro2::TokenStream = stream.into();
     let qt = quote! {
        #input_clone
@@ -170,7 +300,8 @@ pub(crate) fn impl_crud(args: TokenStream, input: TokenStream) -> TokenStream {
 ///     id_name:id|
 ///     id_type:String|
 ///     table_name:biz_activity|
-///     table_columns:id,name,version,delete_flag
+///     table_columns:id,name,version,delete_flag|
+///     id_strategy:snowflake
 fn read_config(arg: &str) -> CrudEnableConfig {
     let keys: Vec<&str> = arg.split("|").collect();
     let mut map = HashMap::new();
@@ -192,5 +323,30 @@ fn read_config(arg: &str) -> CrudEnableConfig {
         id_type: map.get("id_type").unwrap_or(&"".to_string()).to_string(),
         table_name: map.get("table_name").unwrap_or(&"".to_string()).to_string(),
         table_columns: map.get("table_columns").unwrap_or(&"".to_string()).to_string(),
+        id_strategy: map.get("id_strategy").unwrap_or(&"".to_string()).to_string(),
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(code: &str) -> syn::DeriveInput {
+        syn::parse_str(code).unwrap()
+    }
+
+    #[test]
+    fn test_gen_columns() {
+        let ast = parse("pub struct BizActivity { pub id: Option<String>, #[serde(rename = \"activity_name\")] pub name: Option<String>, pub r#type: Option<i32> }");
+        assert_eq!(gen_fields(&ast.data).to_string(), "\"id,activity_name,type\"");
+        let columns = gen_columns(&ast, "").to_string();
+        assert!(columns.contains("pub const COLUMNS : BizActivityColumns"));
+        assert!(columns.contains("name : rbatis :: wrapper :: Column :: new (\"activity_name\")"));
+        assert!(columns.contains("r#type : rbatis :: wrapper :: Column :: new (\"type\")"));
+        assert!(columns.contains("pub name : rbatis :: wrapper :: Column < String >"));
+
+        let columns = gen_columns(&ast, "\"id,type\"").to_string();
+        assert!(columns.contains("Column :: new (\"id\")"));
+        assert!(!columns.contains("activity_name"));
+    }
+}
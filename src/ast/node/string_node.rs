@@ -7,6 +7,7 @@ use crate::core::convert::StmtConvert;
 use crate::core::db::DriverType;
 use crate::engine;
 use crate::engine::runtime::RbatisEngine;
+use crate::sql::ident::is_safe_ident_list;
 use crate::utils::string_util;
 
 ///string抽象节点
@@ -58,11 +59,16 @@ impl RbatisAST for StringNode {
             let v = env.get(item);
             match v {
                 Some(v) => {
-                    if v.is_string() {
-                        result = result.replace(value, &v.as_str().unwrap());
-                    } else {
-                        result = result.replace(value, &v.to_string());
-                    }
+                    //${} is not a sql param,only identifier(table/column name),number,','and asc/desc can be used
+                    let v_str = match v {
+                        Value::String(s) if is_safe_ident_list(s) => s.to_string(),
+                        Value::Number(n) => n.to_string(),
+                        Value::Bool(b) => b.to_string(),
+                        _ => {
+                            return Err(crate::core::Error::from(format!("[rbatis] ${{{}}} value: '{}' must be identifiers or numbers!", item, v)));
+                        }
+                    };
+                    result = result.replace(value, &v_str);
                 }
                 _ => {
                     result = result.replace(value, "");
@@ -85,4 +91,35 @@ pub fn test_string_node() {
 
     let r = s_node.eval(&DriverType::Mysql, &mut john, &mut engine, &mut arg_array).unwrap();
     println!("{}", r);
+}
+
+#[test]
+pub fn test_string_node_unsafe() {
+    let mut arg = json!({
+        "table": "biz_activity; drop table biz_activity",
+    });
+    let mut engine = RbatisEngine::new();
+    let s_node = StringNode::new("select * from ${table}");
+    let mut arg_array = vec![];
+    let r = s_node.eval(&DriverType::Mysql, &mut arg, &mut engine, &mut arg_array);
+    assert!(r.is_err());
+
+    for v in vec![json!("1 OR 1=1"), json!("' OR '1'='1"), json!([1])] {
+        let mut arg = json!({ "id": v });
+        let s_node = StringNode::new("select * from biz_activity where id = ${id}");
+        assert!(s_node.eval(&DriverType::Mysql, &mut arg, &mut engine, &mut arg_array).is_err());
+    }
+
+    let mut arg = json!({ "sort": "create_time desc; drop table biz_activity" });
+    let s_node = StringNode::new("select * from biz_activity order by ${sort}");
+    assert!(s_node.eval(&DriverType::Mysql, &mut arg, &mut engine, &mut arg_array).is_err());
+
+    let mut arg = json!({ "sort": "create_time desc,id asc" });
+    let r = s_node.eval(&DriverType::Mysql, &mut arg, &mut engine, &mut arg_array).unwrap();
+    assert_eq!(r, "select * from biz_activity order by create_time desc,id asc");
+
+    let mut arg = json!({ "table": "t.biz_activity", "id": 1 });
+    let s_node = StringNode::new("select * from ${table} where id = ${id}");
+    let r = s_node.eval(&DriverType::Mysql, &mut arg, &mut engine, &mut arg_array).unwrap();
+    assert_eq!(r, "select * from t.biz_activity where id = 1");
 }
\ No newline at end of file
@@ -0,0 +1,242 @@
+use serde_json::Value;
+
+use crate::core::db::DriverType;
+use crate::core::Error;
+use crate::wrapper::Wrapper;
+
+/// max depth of and/or groups
+const MAX_FILTER_DEPTH: usize = 16;
+
+impl Wrapper {
+    /// make Wrapper from json filter, the filter is safe to accept from client,
+    /// because every column must in allowed_columns(include the columns in and/or groups),
+    /// and every value is a sql param. allowed_columns can not be empty.
+    ///
+    /// filter grammar, every node is an object with only one key:
+    ///   {"and":[node,node...]}           (node AND node)
+    ///   {"or":[node,node...]}            (node OR node)
+    ///   {"eq":["column",value]}          column = ?    (also "ne","gt","ge","lt","le")
+    ///   {"like":["column","value"]}      column LIKE ? (also "not_like")
+    ///   {"in":["column",[value,...]]}    column IN (?,?) (also "not_in")
+    ///   {"between":["column",min,max]}   column BETWEEN ? AND ? (also "not_between")
+    ///   {"is_null":"column"}             column IS NULL (also "is_not_null")
+    /// value must be string/number/bool.
+    ///
+    /// for Example:
+    ///   let filter = json!({"and":[{"eq":["status",1]},{"or":[{"like":["name","foo"]},{"is_null":"name"}]}]});
+    ///   let w = Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status", "name"])?;
+    ///   //sql: status = ? AND (name LIKE ? OR name IS NULL)
+    pub fn from_filter_json(driver_type: &DriverType, filter: &Value, allowed_columns: &[&str]) -> Result<Wrapper, Error> {
+        if allowed_columns.is_empty() {
+            return Err(Error::from("[rbatis] filter allowed_columns can not be empty!"));
+        }
+        let mut w = Wrapper::new(driver_type);
+        w.allow_columns(allowed_columns);
+        push_filter(&mut w, filter, 0);
+        let mut w = w.check()?;
+        w.sql = w.sql.trim().to_string();
+        Ok(w)
+    }
+}
+
+fn set_error(w: &mut Wrapper, msg: String) {
+    if w.error.is_none() {
+        w.error = Some(Error::from(format!("[rbatis] filter {}", msg)));
+    }
+}
+
+fn push_filter(w: &mut Wrapper, node: &Value, depth: usize) {
+    if w.error.is_some() {
+        return;
+    }
+    if depth > MAX_FILTER_DEPTH {
+        set_error(w, format!("depth can not more than {}!", MAX_FILTER_DEPTH));
+        return;
+    }
+    let obj = match node.as_object() {
+        Some(obj) if obj.len() == 1 => obj,
+        _ => {
+            set_error(w, format!("node must be an object with one key: {}", node));
+            return;
+        }
+    };
+    let (op, arg) = obj.iter().next().unwrap();
+    match op.as_str() {
+        "and" | "or" => {
+            let is_and = op.eq("and");
+            let items = match arg.as_array() {
+                Some(items) => items,
+                None => {
+                    set_error(w, format!("'{}' must be an array!", op));
+                    return;
+                }
+            };
+            for item in items {
+                if is_group(item) {
+                    if is_and {
+                        w.and_nested(|child| {
+                            push_filter(child, item, depth + 1);
+                            child
+                        });
+                    } else {
+                        w.or_nested(|child| {
+                            push_filter(child, item, depth + 1);
+                            child
+                        });
+                    }
+                } else {
+                    if !is_and {
+                        w.or();
+                    }
+                    push_filter(w, item, depth + 1);
+                }
+            }
+        }
+        "eq" | "ne" | "gt" | "ge" | "lt" | "le" | "like" | "not_like" => {
+            let args = match filter_args(arg, 2) {
+                Ok(args) => args,
+                Err(e) => {
+                    set_error(w, format!("'{}' {}", op, e));
+                    return;
+                }
+            };
+            let (column, v) = (args[0].as_str().unwrap(), &args[1]);
+            if !is_scalar(v) {
+                set_error(w, format!("'{}' value must be string/number/bool!", op));
+                return;
+            }
+            match op.as_str() {
+                "eq" => w.eq(column, v),
+                "ne" => w.ne(column, v),
+                "gt" => w.gt(column, v),
+                "ge" => w.ge(column, v),
+                "lt" => w.lt(column, v),
+                "le" => w.le(column, v),
+                "like" => w.like(column, v),
+                _ => w.not_like(column, v),
+            };
+        }
+        "in" | "not_in" => {
+            let args = match filter_args(arg, 2) {
+                Ok(args) => args,
+                Err(e) => {
+                    set_error(w, format!("'{}' {}", op, e));
+                    return;
+                }
+            };
+            let column = args[0].as_str().unwrap();
+            let values = match args[1].as_array() {
+                Some(values) if !values.is_empty() && values.iter().all(is_scalar) => values,
+                _ => {
+                    set_error(w, format!("'{}' values must be a not empty array of string/number/bool!", op));
+                    return;
+                }
+            };
+            if op.eq("in") {
+                w.in_array(column, values);
+            } else {
+                w.not_in(column, values);
+            }
+        }
+        "between" | "not_between" => {
+            let args = match filter_args(arg, 3) {
+                Ok(args) => args,
+                Err(e) => {
+                    set_error(w, format!("'{}' {}", op, e));
+                    return;
+                }
+            };
+            let column = args[0].as_str().unwrap();
+            if !is_scalar(&args[1]) || !is_scalar(&args[2]) {
+                set_error(w, format!("'{}' value must be string/number/bool!", op));
+                return;
+            }
+            if op.eq("between") {
+                w.between(column, &args[1], &args[2]);
+            } else {
+                w.not_between(column, &args[1], &args[2]);
+            }
+        }
+        "is_null" | "is_not_null" => {
+            let column = match arg {
+                Value::String(column) => column.as_str(),
+                _ => {
+                    set_error(w, format!("'{}' must be a column string!", op));
+                    return;
+                }
+            };
+            if op.eq("is_null") {
+                w.is_null(column);
+            } else {
+                w.is_not_null(column);
+            }
+        }
+        _ => {
+            set_error(w, format!("not support operator: '{}'", op));
+        }
+    }
+}
+
+fn is_group(node: &Value) -> bool {
+    match node.as_object() {
+        Some(obj) => obj.contains_key("and") || obj.contains_key("or"),
+        None => false
+    }
+}
+
+fn is_scalar(v: &Value) -> bool {
+    v.is_string() || v.is_number() || v.is_boolean()
+}
+
+/// the args must be an array: ["column",arg...], and the column must be string
+fn filter_args(arg: &Value, len: usize) -> Result<&Vec<Value>, String> {
+    match arg.as_array() {
+        Some(args) if args.len() == len && args[0].is_string() => Ok(args),
+        _ => Err(format!("must be an array of [\"column\",{} value]!", len - 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::db::DriverType;
+    use crate::wrapper::Wrapper;
+
+    #[test]
+    fn test_from_filter_json() {
+        let filter = json!({"and":[{"eq":["status",1]},{"or":[{"like":["name","foo"]},{"is_null":"name"}]}]});
+        let w = Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status", "name"]).unwrap();
+        assert_eq!(w.sql, "status = ? AND (name LIKE ? OR name IS NULL)");
+        assert_eq!(w.args, vec![json!(1), json!("%foo%")]);
+
+        let filter = json!({"or":[{"in":["id",[1,2]]},{"between":["sort",1,10]}]});
+        let w = Wrapper::from_filter_json(&DriverType::Postgres, &filter, &["id", "sort"]).unwrap();
+        assert_eq!(w.sql, "id IN ( $1 , $2 ) OR sort BETWEEN $3 AND $4");
+    }
+
+    #[test]
+    fn test_from_filter_json_reject() {
+        //column not allowed
+        let filter = json!({"eq":["password","1"]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status", "name"]).is_err());
+        //column not allowed in nested group
+        let filter = json!({"and":[{"or":[{"eq":["password","x"]}]}]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status"]).is_err());
+        let filter = json!({"or":[{"eq":["status",1]},{"and":[{"eq":["status",2]},{"is_null":"password"}]}]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status"]).is_err());
+        //empty allowed_columns
+        let filter = json!({"eq":["status",1]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &[]).is_err());
+        //invalid column
+        let filter = json!({"eq":["1=1 or name","1"]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["name"]).is_err());
+        //unknown operator
+        let filter = json!({"raw":"1=1"});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status"]).is_err());
+        //value not scalar
+        let filter = json!({"eq":["status",{"a":1}]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status"]).is_err());
+        //empty in
+        let filter = json!({"in":["status",[]]});
+        assert!(Wrapper::from_filter_json(&DriverType::Mysql, &filter, &["status"]).is_err());
+    }
+}
@@ -22,5 +22,8 @@ pub mod rbatis;
 pub mod sql;
 pub mod crud;
 pub mod wrapper;
+pub mod update_wrapper;
 pub mod tx;
+pub mod relation;
+pub mod filter;
 
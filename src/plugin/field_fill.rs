@@ -0,0 +1,96 @@
+use chrono::NaiveDateTime;
+use serde_json::{Map, Value};
+
+use crate::core::db::DriverType;
+use crate::core::value::DateTimeNow;
+
+/// Field Fill Plugin trait,fill the column value map before insert/update sql is created
+/// for example: create_time,update_time,tenant_id,created_by
+pub trait FieldFill: Send + Sync {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+    /// fill the column value map(from CRUDEnable::make_column_value_map)
+    /// action: Insert(save,save_batch) or Update(update_by_id,update_by_wrapper)
+    fn fill(&self, driver_type: &DriverType, action: &FillAction, table_name: &str, map: &mut Map<String, Value>) -> Result<(), crate::core::Error>;
+}
+
+pub enum FillAction {
+    Insert,
+    Update,
+}
+
+/// fill now time, if the column exist
+/// insert_columns: fill when insert and value is null, for example: create_time
+/// update_columns: fill when insert and value is null, always overwrite when update, for example: update_time
+pub struct RbatisFieldFillPlugin {
+    pub insert_columns: Vec<String>,
+    pub update_columns: Vec<String>,
+}
+
+impl RbatisFieldFillPlugin {
+    pub fn new(insert_columns: &[&str], update_columns: &[&str]) -> Self {
+        Self {
+            insert_columns: insert_columns.iter().map(|x| x.to_string()).collect(),
+            update_columns: update_columns.iter().map(|x| x.to_string()).collect(),
+        }
+    }
+
+    fn fill_now(columns: &Vec<String>, map: &mut Map<String, Value>, overwrite: bool) {
+        for column in columns {
+            match map.get_mut(column) {
+                Some(v) => {
+                    if overwrite || v.is_null() {
+                        *v = json!(NaiveDateTime::now());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl FieldFill for RbatisFieldFillPlugin {
+    fn fill(&self, _driver_type: &DriverType, action: &FillAction, _table_name: &str, map: &mut Map<String, Value>) -> Result<(), crate::core::Error> {
+        match action {
+            FillAction::Insert => {
+                Self::fill_now(&self.insert_columns, map, false);
+                Self::fill_now(&self.update_columns, map, false);
+            }
+            FillAction::Update => {
+                Self::fill_now(&self.update_columns, map, true);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_fill_plugin() {
+        let r = RbatisFieldFillPlugin::new(&["create_time"], &["update_time"]);
+        let mut map = Map::new();
+        map.insert("create_time".to_string(), Value::Null);
+        map.insert("update_time".to_string(), Value::Null);
+        r.fill(&DriverType::Mysql, &FillAction::Update, "test", &mut map).unwrap();
+        assert!(map.get("create_time").unwrap().is_null());
+        assert!(map.get("update_time").unwrap().is_string());
+        r.fill(&DriverType::Mysql, &FillAction::Insert, "test", &mut map).unwrap();
+        assert!(map.get("create_time").unwrap().is_string());
+        assert!(!map.contains_key("tenant_id"));
+
+        //the fetched entity keep the old update_time,update must refresh it
+        let old_time = json!("2020-01-01T00:00:00");
+        map.insert("create_time".to_string(), old_time.clone());
+        map.insert("update_time".to_string(), old_time.clone());
+        r.fill(&DriverType::Mysql, &FillAction::Update, "test", &mut map).unwrap();
+        assert_eq!(map.get("create_time").unwrap(), &old_time);
+        assert_ne!(map.get("update_time").unwrap(), &old_time);
+        r.fill(&DriverType::Mysql, &FillAction::Insert, "test", &mut map).unwrap();
+        assert_eq!(map.get("create_time").unwrap(), &old_time);
+    }
+}
@@ -12,6 +12,10 @@ pub trait LogPlugin: Send + Sync {
     fn is_enable(&self) -> bool{
         return !self.get_level_filter().eq(&log::LevelFilter::Off);
     }
+    /// print debug sql(args inlined) after args,debug only
+    fn is_debug_sql(&self) -> bool {
+        false
+    }
     fn do_log(&self, data: &str) {
         match self.get_level_filter() {
             log::LevelFilter::Error => {
@@ -40,13 +44,16 @@ pub trait LogPlugin: Send + Sync {
 }
 
 pub struct RbatisLog {
-    pub level_filter: LevelFilter
+    pub level_filter: LevelFilter,
+    /// print debug sql,default false
+    pub debug_sql: bool,
 }
 
 impl Default for RbatisLog {
     fn default() -> Self {
         Self {
-            level_filter: log::LevelFilter::Info
+            level_filter: log::LevelFilter::Info,
+            debug_sql: false,
         }
     }
 }
@@ -56,6 +63,10 @@ impl LogPlugin for RbatisLog {
         &self.level_filter
     }
 
+    fn is_debug_sql(&self) -> bool {
+        self.debug_sql
+    }
+
     fn error(&self, data: &str) {
         error!("{}", data);
     }
@@ -2,6 +2,7 @@ use serde_json::Value;
 
 use crate::core::db::DriverType;
 use crate::core::Error;
+use crate::wrapper::is_tail_clause;
 
 /// Logic Delete Plugin trait
 pub trait LogicDelete: Send + Sync {
@@ -11,12 +12,28 @@ pub trait LogicDelete: Send + Sync {
     }
     /// database column
     fn column(&self) -> &str;
-    /// deleted data,must be i32
-    fn deleted(&self) -> i32;
-    /// un deleted data,must be i32
-    fn un_deleted(&self) -> i32;
+    /// deleted data sql,for example: "1" or "CURRENT_TIMESTAMP"
+    fn deleted(&self) -> String;
+    /// un deleted data sql,for example: "0" or "NULL"
+    fn un_deleted(&self) -> String;
+    /// the un deleted where sql,for example: "del = 0" or "deleted_at IS NULL"
+    fn un_deleted_where(&self) -> String {
+        let un_deleted = self.un_deleted();
+        if un_deleted.trim().eq_ignore_ascii_case("NULL") {
+            format!("{} IS NULL", self.column())
+        } else {
+            format!("{} = {}", self.column(), un_deleted)
+        }
+    }
     /// create_remove_sql
     fn create_remove_sql(&self, driver_type: &DriverType, table_name: &str, table_fields: &str, sql_where: &str) -> Result<String, crate::core::Error>;
+    /// create_restore_sql, set column to un_deleted
+    fn create_restore_sql(&self, driver_type: &DriverType, table_name: &str, table_fields: &str, sql_where: &str) -> Result<String, crate::core::Error> {
+        if !table_fields.contains(self.column()) {
+            return Err(Error::from(format!("[rbatis] restore data must have logic delete column: {}", self.column())));
+        }
+        Ok(format!("UPDATE {} SET {} = {}", table_name, self.column(), self.un_deleted()) + sql_where)
+    }
     /// create_select_sql
     fn create_select_sql(&self, driver_type: &DriverType, table_name: &str, table_fields: &str, sql_where: &str) -> Result<String, crate::core::Error>;
 }
@@ -28,16 +45,16 @@ pub enum LogicAction {
 
 pub struct RbatisLogicDeletePlugin {
     pub column: String,
-    pub deleted: i32,
-    pub un_deleted: i32,
+    pub deleted: String,
+    pub un_deleted: String,
 }
 
 impl RbatisLogicDeletePlugin {
     pub fn new(column: &str) -> Self {
         Self {
             column: column.to_string(),
-            deleted: 1,
-            un_deleted: 0,
+            deleted: "1".to_string(),
+            un_deleted: "0".to_string(),
         }
     }
     pub fn new_opt(column: &str, deleted: i32, un_deleted: i32) -> Self {
@@ -46,8 +63,17 @@ impl RbatisLogicDeletePlugin {
         }
         Self {
             column: column.to_string(),
-            deleted,
-            un_deleted,
+            deleted: deleted.to_string(),
+            un_deleted: un_deleted.to_string(),
+        }
+    }
+
+    /// use timestamp column,for example "deleted_at": deleted is CURRENT_TIMESTAMP,un deleted is NULL
+    pub fn new_time(column: &str) -> Self {
+        Self {
+            column: column.to_string(),
+            deleted: "CURRENT_TIMESTAMP".to_string(),
+            un_deleted: "NULL".to_string(),
         }
     }
 }
@@ -57,12 +83,12 @@ impl LogicDelete for RbatisLogicDeletePlugin {
         self.column.as_str()
     }
 
-    fn deleted(&self) -> i32 {
-        self.deleted
+    fn deleted(&self) -> String {
+        self.deleted.clone()
     }
 
-    fn un_deleted(&self) -> i32 {
-        self.un_deleted
+    fn un_deleted(&self) -> String {
+        self.un_deleted.clone()
     }
 
 
@@ -84,12 +110,12 @@ impl LogicDelete for RbatisLogicDeletePlugin {
         let mut sql = String::new();
         if table_fields.contains(self.column()) {
             if where_sql.is_empty() {
-                where_sql = format!("{} = {}", self.column(), self.un_deleted());
+                where_sql = self.un_deleted_where();
             } else {
-                if where_sql.starts_with("ORDER BY") || where_sql.starts_with("GROUP BY") {
-                    where_sql = format!("{} = {} ", self.column(), self.un_deleted()) + where_sql.as_str();
+                if is_tail_clause(&where_sql) {
+                    where_sql = format!("{} ", self.un_deleted_where()) + where_sql.as_str();
                 } else {
-                    where_sql = format!("{} = {} AND ", self.column(), self.un_deleted()) + where_sql.as_str();
+                    where_sql = format!("{} AND ", self.un_deleted_where()) + where_sql.as_str();
                 }
             }
         }
@@ -128,4 +154,18 @@ mod tests {
         let result = r.create_remove_sql(&DriverType::Mysql, "test", &table_fields, sql_where);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_logic_delete_plugin_time() {
+        let r = RbatisLogicDeletePlugin::new_time("deleted_at");
+        let table_fields = "name,deleted_at";
+        let result = r.create_remove_sql(&DriverType::Mysql, "test", table_fields, " WHERE name = ?").unwrap();
+        assert_eq!("UPDATE test SET deleted_at = CURRENT_TIMESTAMP WHERE name = ?", &result);
+
+        let result = r.create_restore_sql(&DriverType::Mysql, "test", table_fields, " WHERE name = ?").unwrap();
+        assert_eq!("UPDATE test SET deleted_at = NULL WHERE name = ?", &result);
+
+        let result = r.create_select_sql(&DriverType::Mysql, "test", table_fields, "name = ?").unwrap();
+        assert_eq!("SELECT name,deleted_at FROM test WHERE deleted_at IS NULL AND name = ?", &result);
+    }
 }
\ No newline at end of file
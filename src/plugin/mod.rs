@@ -2,6 +2,9 @@ pub mod page;
 pub mod logic_delete;
 pub mod intercept;
 pub mod log;
+pub mod version_lock;
+pub mod field_fill;
+pub mod tx_event;
 
 #[cfg(feature = "snowflake")]
 pub mod snowflake;
\ No newline at end of file
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+/// the event kind of tx
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TxEventKind {
+    Begin,
+    Commit,
+    Rollback,
+    /// rollback by TxManager,because the tx is out of time
+    TimeoutRollback,
+}
+
+#[derive(Clone, Debug)]
+pub struct TxEvent<'a> {
+    pub tx_id: &'a str,
+    pub kind: TxEventKind,
+    /// the time from begin
+    pub age: Duration,
+    /// the savepoint name if the event is fired by begin_propagation(Propagation::Nested) and it's commit/rollback
+    pub savepoint: Option<&'a str>,
+    /// false if the commit/rollback fail
+    pub success: bool,
+}
+
+/// tx event plugin,called by TxManager when tx begin/commit/rollback(success or fail),for example export metrics
+pub trait TxEventPlugin: Send + Sync {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+    fn on_event(&self, event: &TxEvent);
+}
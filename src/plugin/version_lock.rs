@@ -0,0 +1,64 @@
+use serde_json::Value;
+
+use crate::core::Error;
+
+/// Version Lock Plugin trait(optimistic lock)
+///
+/// if table have the version column and the arg's version is not null,update sql will be:
+///   UPDATE biz_activity SET name = ?, version = version + 1 WHERE version = ? AND (id = ?)
+/// and return error when rows_affected is 0
+pub trait VersionLock: Send + Sync {
+    ///the name
+    fn name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+    /// database column
+    fn column(&self) -> &str;
+    /// the set sql, default is 'version = version + 1'
+    fn create_set_sql(&self) -> String {
+        format!("{} = {} + 1", self.column(), self.column())
+    }
+    /// the error when update fail(rows_affected is 0)
+    fn create_lock_error(&self, table_name: &str, version: &Value) -> Error {
+        Error::VersionLock {
+            table: table_name.to_string(),
+            column: self.column().to_string(),
+            version: version.clone(),
+        }
+    }
+}
+
+/// version lock error prefix,use Error::is_version_lock() to check the error
+pub const VERSION_LOCK_ERROR: &'static str = "[rbatis] version lock fail!";
+
+pub struct RbatisVersionLockPlugin {
+    pub column: String,
+}
+
+impl RbatisVersionLockPlugin {
+    pub fn new(column: &str) -> Self {
+        Self {
+            column: column.to_string(),
+        }
+    }
+}
+
+impl VersionLock for RbatisVersionLockPlugin {
+    fn column(&self) -> &str {
+        self.column.as_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_lock_plugin() {
+        let r = RbatisVersionLockPlugin::new("version");
+        assert_eq!("version = version + 1", r.create_set_sql());
+        let e = r.create_lock_error("test", &serde_json::json!(1));
+        assert!(e.is_version_lock());
+        assert!(e.to_string().starts_with(VERSION_LOCK_ERROR));
+    }
+}
@@ -1,6 +1,7 @@
 use std::borrow::BorrowMut;
 use std::cell::Cell;
 use std::collections::HashMap;
+use std::future::Future;
 use std::time::Duration;
 
 use once_cell::sync::OnceCell;
@@ -13,18 +14,22 @@ use crate::ast::lang::py::Py;
 use crate::ast::node::node::do_child_nodes;
 use crate::ast::node::node_type::NodeType;
 use crate::ast::node::proxy_node::CustomNodeGenerate;
-use crate::core::db::{DriverType, PoolOptions};
+use crate::core::db::{DriverType, PoolOptions, TxOptions};
 use crate::core::db_adapter::{DBExecResult, DBPool, DBPoolConn, DBQuery, DBTx};
 use crate::core::Error;
 use crate::core::runtime::Arc;
 use crate::core::sync::sync_map::SyncMap;
 use crate::engine::runtime::RbatisEngine;
+use crate::plugin::field_fill::FieldFill;
 use crate::plugin::intercept::SqlIntercept;
 use crate::plugin::log::{LogPlugin, RbatisLog};
 use crate::plugin::logic_delete::{LogicDelete, RbatisLogicDeletePlugin};
 use crate::plugin::page::{IPage, IPageRequest, Page, PagePlugin, RbatisPagePlugin};
-use crate::sql::PageLimit;
-use crate::tx::{TxManager, TxState};
+use crate::plugin::tx_event::TxEventPlugin;
+use crate::plugin::version_lock::VersionLock;
+use crate::sql::{DebugSql, PageLimit};
+use crate::tx::{Propagation, TxGuard, TxManager, TxState};
+use crate::utils::catch_unwind::CatchUnwind;
 use crate::utils::error_util::ToResult;
 use crate::utils::string_util;
 use crate::wrapper::Wrapper;
@@ -45,8 +50,18 @@ pub struct Rbatis {
     pub sql_intercepts: Vec<Box<dyn SqlIntercept>>,
     // logic delete plugin
     pub logic_plugin: Option<Box<dyn LogicDelete>>,
+    // version lock plugin
+    pub version_lock_plugin: Option<Box<dyn VersionLock>>,
+    // field fill plugin
+    pub field_fill_plugin: Option<Box<dyn FieldFill>>,
     // log plugin
     pub log_plugin: Arc<Box<dyn LogPlugin>>,
+    // the max attempts of serialization failure or deadlock,1 is not retry
+    pub retry_max_attempts: u32,
+    // the backoff of first retry,next retry backoff * 2
+    pub retry_backoff: Duration,
+    // the max backoff of retry
+    pub retry_max_backoff: Duration,
 }
 
 impl Default for Rbatis {
@@ -84,8 +99,20 @@ pub struct RbatisOption {
     pub sql_intercepts: Vec<Box<dyn SqlIntercept>>,
     /// logic delete plugin
     pub logic_plugin: Option<Box<dyn LogicDelete>>,
+    /// version lock plugin
+    pub version_lock_plugin: Option<Box<dyn VersionLock>>,
+    /// field fill plugin
+    pub field_fill_plugin: Option<Box<dyn FieldFill>>,
     /// log plugin
     pub log_plugin: Arc<Box<dyn LogPlugin>>,
+    /// tx event plugin
+    pub tx_event_plugin: Option<Arc<Box<dyn TxEventPlugin>>>,
+    /// the max attempts of serialization failure or deadlock(sql without tx,transaction_retry()),default 1 is not retry
+    pub retry_max_attempts: u32,
+    /// the backoff of first retry,next retry backoff * 2,the sleep time is random in [backoff/2,backoff]
+    pub retry_backoff: Duration,
+    /// the max backoff of retry,default 2s
+    pub retry_max_backoff: Duration,
 }
 
 impl Default for RbatisOption {
@@ -97,7 +124,13 @@ impl Default for RbatisOption {
             page_plugin: Box::new(RbatisPagePlugin {}),
             sql_intercepts: vec![],
             logic_plugin: None,
+            version_lock_plugin: None,
+            field_fill_plugin: None,
             log_plugin: Arc::new(Box::new(RbatisLog::default()) as Box<dyn LogPlugin>),
+            tx_event_plugin: None,
+            retry_max_attempts: 1,
+            retry_backoff: Duration::from_millis(50),
+            retry_max_backoff: Duration::from_secs(2),
         }
     }
 }
@@ -112,11 +145,16 @@ impl Rbatis {
         return Self {
             pool: OnceCell::new(),
             engine: RbatisEngine::new(),
-            tx_manager: TxManager::new_arc(option.log_plugin.clone(), option.tx_lock_wait_timeout, option.tx_check_interval),
+            tx_manager: TxManager::new_arc(option.log_plugin.clone(), option.tx_event_plugin, option.tx_lock_wait_timeout, option.tx_check_interval),
             page_plugin: option.page_plugin,
             sql_intercepts: option.sql_intercepts,
             logic_plugin: option.logic_plugin,
+            version_lock_plugin: option.version_lock_plugin,
+            field_fill_plugin: option.field_fill_plugin,
             log_plugin: option.log_plugin,
+            retry_max_attempts: option.retry_max_attempts,
+            retry_backoff: option.retry_backoff,
+            retry_max_backoff: option.retry_max_backoff,
             py: Py { cache: Default::default(), generate: option.generate },
         };
     }
@@ -168,6 +206,28 @@ impl Rbatis {
         }
     }
 
+    pub fn set_version_lock_plugin<T>(&mut self, arg: Option<T>) where T: VersionLock + 'static {
+        match arg {
+            Some(v) => {
+                self.version_lock_plugin = Some(Box::new(v));
+            }
+            None => {
+                self.version_lock_plugin = None;
+            }
+        }
+    }
+
+    pub fn set_field_fill_plugin<T>(&mut self, arg: Option<T>) where T: FieldFill + 'static {
+        match arg {
+            Some(v) => {
+                self.field_fill_plugin = Some(Box::new(v));
+            }
+            None => {
+                self.field_fill_plugin = None;
+            }
+        }
+    }
+
     pub fn set_page_plugin<T>(&mut self, arg: T) where T: PagePlugin + 'static {
         self.page_plugin = Box::new(arg);
     }
@@ -207,6 +267,111 @@ impl Rbatis {
         return Ok(result);
     }
 
+    /// begin tx with options,for new conn
+    ///for example:
+    ///         use rbatis::core::{IsolationLevel, TxOptions};
+    ///         rb.begin_with("tx:1", &TxOptions::new().isolation(IsolationLevel::Serializable).read_only(true)).await?;
+    pub async fn begin_with(&self, new_tx_id: &str, options: &TxOptions) -> Result<u64, crate::core::Error> {
+        let result = self.tx_manager.begin_with(new_tx_id, self.get_pool()?, options).await?;
+        if self.log_plugin.is_enable() {
+            self.log_plugin.do_log(&format!("[rbatis] [{}] Begin {:?}", new_tx_id, options));
+        }
+        return Ok(result);
+    }
+
+    /// begin tx with propagation,if the tx_id already begin:
+    /// Required join it, RequiresNew suspend it and begin a new tx, Nested create a savepoint on it.
+    /// every begin_propagation() must match one commit()/rollback()
+    pub async fn begin_propagation(&self, tx_id: &str, propagation: Propagation) -> Result<u64, crate::core::Error> {
+        let result = self.tx_manager.begin_propagation(tx_id, self.get_pool()?, propagation).await?;
+        if self.log_plugin.is_enable() {
+            self.log_plugin.do_log(&format!("[rbatis] [{}] Begin {:?}", tx_id, propagation));
+        }
+        return Ok(result);
+    }
+
+    /// begin tx with a new tx_id,and return the TxGuard.
+    /// the tx will be rollback if the TxGuard drop without commit
+    pub async fn begin_defer(&self) -> Result<TxGuard<'_>, crate::core::Error> {
+        let tx_id = format!("tx:{}", uuid::Uuid::new_v4());
+        self.begin(&tx_id).await?;
+        Ok(TxGuard::new(self, &tx_id))
+    }
+
+    /// run the closure in a new tx,commit if return Ok,rollback if return Err or panic.
+    ///for example:
+    ///
+    ///         let r: u64 = rb.transaction(|tx_id| async move {
+    ///             rb.save(&tx_id, &activity).await?;
+    ///             rb.remove_by_id::<BizActivity>(&tx_id, &"2".to_string()).await
+    ///         }).await?;
+    ///
+    pub async fn transaction<F, Fut, R>(&self, f: F) -> Result<R, crate::core::Error>
+        where F: FnOnce(String) -> Fut,
+              Fut: Future<Output=Result<R, crate::core::Error>> {
+        let tx_id = format!("tx:{}", uuid::Uuid::new_v4());
+        self.begin(&tx_id).await?;
+        match CatchUnwind::new(f(tx_id.clone())).await {
+            Ok(Ok(r)) => {
+                self.commit(&tx_id).await?;
+                Ok(r)
+            }
+            Ok(Err(e)) => {
+                if let Err(rollback_err) = self.rollback(&tx_id).await {
+                    if self.log_plugin.is_enable() {
+                        self.log_plugin.do_log(&format!("[rbatis] [{}] Rollback fail:{}", tx_id, rollback_err));
+                    }
+                }
+                Err(e)
+            }
+            Err(panic) => {
+                if self.log_plugin.is_enable() {
+                    self.log_plugin.do_log(&format!("[rbatis] [{}] Rollback,because the transaction panic", tx_id));
+                }
+                let _ = self.rollback(&tx_id).await;
+                std::panic::resume_unwind(panic)
+            }
+        }
+    }
+
+    /// same as transaction(),but the closure will be run again in a new tx(max retry_max_attempts) if return serialization failure or deadlock error
+    ///for example:
+    ///
+    ///         let r: u64 = rb.transaction_retry(|tx_id| async move {
+    ///             rb.remove_by_id::<BizActivity>(&tx_id, &"2".to_string()).await
+    ///         }).await?;
+    ///
+    pub async fn transaction_retry<F, Fut, R>(&self, f: F) -> Result<R, crate::core::Error>
+        where F: Fn(String) -> Fut,
+              Fut: Future<Output=Result<R, crate::core::Error>> {
+        let f = &f;
+        self.retry(|| self.transaction(f)).await
+    }
+
+    /// run f again if return serialization failure or deadlock error,max retry_max_attempts
+    async fn retry<F, Fut, R>(&self, f: F) -> Result<R, crate::core::Error>
+        where F: Fn() -> Fut,
+              Fut: Future<Output=Result<R, crate::core::Error>> {
+        let mut attempt = 1;
+        let mut backoff = self.retry_backoff;
+        loop {
+            match f().await {
+                Err(e) if e.is_retryable() && attempt < self.retry_max_attempts => {
+                    let sleep = retry_jitter(backoff);
+                    if self.log_plugin.is_enable() {
+                        self.log_plugin.do_log(&format!("[rbatis] retry attempt:{},after:{:?},because:{}", attempt + 1, sleep, e));
+                    }
+                    crate::core::runtime::sleep(sleep).await;
+                    attempt += 1;
+                    backoff = next_retry_backoff(backoff, self.retry_max_backoff);
+                }
+                result => {
+                    return result;
+                }
+            }
+        }
+    }
+
     /// commit tx,and return conn
     pub async fn commit(&self, tx_id: &str) -> Result<u64, crate::core::Error> {
         let result = self.tx_manager.commit(tx_id).await?;
@@ -241,8 +406,11 @@ impl Rbatis {
         let result;
         let mut fetch_num = 0;
         if tx_id.is_empty() {
-            let mut conn = self.get_pool()?.acquire().await?;
-            let (data, num) = conn.fetch(sql.as_str()).await?;
+            let sql = &sql;
+            let (data, num) = self.retry(|| async move {
+                let mut conn = self.get_pool()?.acquire().await?;
+                conn.fetch(sql.as_str()).await
+            }).await?;
             result = data;
             fetch_num = num;
         } else {
@@ -251,6 +419,7 @@ impl Rbatis {
                 return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
             }
             let mut conn = conn.unwrap();
+            conn.value_mut().1.last_sql = sql.to_string();
             let (data, num) = conn.value_mut().0.fetch(sql.as_str()).await?;
             result = data;
             fetch_num = num;
@@ -274,14 +443,18 @@ impl Rbatis {
         }
         let data;
         if tx_id.is_empty() {
-            let mut conn = self.get_pool()?.acquire().await?;
-            data = conn.execute(&sql).await?;
+            let sql = &sql;
+            data = self.retry(|| async move {
+                let mut conn = self.get_pool()?.acquire().await?;
+                conn.execute(sql).await
+            }).await?;
         } else {
             let conn = self.tx_manager.get_mut(tx_id).await;
             if conn.is_none() {
                 return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
             }
             let mut conn = conn.unwrap();
+            conn.value_mut().1.last_sql = sql.to_string();
             data = conn.value_mut().0.execute(&sql).await?;
         }
         if self.log_plugin.is_enable() {
@@ -311,13 +484,17 @@ impl Rbatis {
         }
         if self.log_plugin.is_enable() {
             self.log_plugin.do_log(&format!("[rbatis] [{}] Query ==> {}\n{}[rbatis] [{}] Args ==> {}", tx_id, &sql, string_util::LOG_SPACE, tx_id, serde_json::Value::Array(args.clone()).to_string()));
+            self.log_debug_sql(tx_id, &sql, &args);
         }
         let result_data;
         let mut return_num = 0;
         if tx_id.is_empty() {
-            let mut conn = self.get_pool()?.acquire().await?;
-            let q: DBQuery = self.bind_arg(&sql, &args)?;
-            let (result, num) = conn.fetch_parperd(q).await?;
+            let (sql, args) = (&sql, &args);
+            let (result, num) = self.retry(|| async move {
+                let mut conn = self.get_pool()?.acquire().await?;
+                let q: DBQuery = self.bind_arg(sql, args)?;
+                conn.fetch_parperd(q).await
+            }).await?;
             result_data = result;
             return_num = num;
         } else {
@@ -327,6 +504,7 @@ impl Rbatis {
                 return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
             }
             let mut conn = conn.unwrap();
+            conn.value_mut().1.last_sql = sql.to_string();
             let (result, num) = conn.value_mut().0.fetch_parperd(q).await?;
             result_data = result;
             return_num = num;
@@ -348,12 +526,16 @@ impl Rbatis {
         }
         if self.log_plugin.is_enable() {
             self.log_plugin.do_log(&format!("[rbatis] [{}] Exec ==> {}\n{}[rbatis] [{}] Args ==> {}", tx_id, &sql, string_util::LOG_SPACE, tx_id, serde_json::Value::Array(args.clone()).to_string()));
+            self.log_debug_sql(tx_id, &sql, &args);
         }
         let result;
         if tx_id.is_empty() {
-            let q: DBQuery = self.bind_arg(&sql, &args)?;
-            let mut conn = self.get_pool()?.acquire().await?;
-            result = conn.exec_prepare(q).await;
+            let (sql, args) = (&sql, &args);
+            result = self.retry(|| async move {
+                let q: DBQuery = self.bind_arg(sql, args)?;
+                let mut conn = self.get_pool()?.acquire().await?;
+                conn.exec_prepare(q).await
+            }).await;
         } else {
             let q: DBQuery = self.bind_arg(&sql, &args)?;
             let conn = self.tx_manager.get_mut(tx_id).await;
@@ -361,6 +543,7 @@ impl Rbatis {
                 return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
             }
             let mut conn = conn.unwrap();
+            conn.value_mut().1.last_sql = sql.to_string();
             result = conn.value_mut().0.exec_prepare(q).await;
         }
         if self.log_plugin.is_enable() {
@@ -384,6 +567,24 @@ impl Rbatis {
         self.exec_prepare(tx_id, w.sql.as_str(), &w.args).await
     }
 
+    fn log_debug_sql(&self, tx_id: &str, sql: &str, args: &Vec<serde_json::Value>) {
+        if self.log_plugin.is_debug_sql() {
+            if let Ok(driver_type) = self.driver_type() {
+                self.log_plugin.do_log(&format!("[rbatis] [{}] DebugSql(debug only) ==> {}", tx_id, driver_type.debug_sql(sql, args)));
+            }
+        }
+    }
+
+    /// debug only! return the py sql with inlined args,do not execute it
+    ///for example:
+    ///         let sql = rb.py_to_debug_sql("SELECT * FROM biz_activity WHERE delete_flag = #{delete_flag}", &json!({ "delete_flag": 1 })).unwrap();
+    ///         //sql: SELECT * FROM biz_activity WHERE delete_flag = 1
+    pub fn py_to_debug_sql<Ser>(&self, py: &str, arg: &Ser) -> Result<String, crate::core::Error>
+        where Ser: Serialize {
+        let (sql, args) = self.py_to_sql(py, &json!(arg))?;
+        Ok(self.driver_type()?.debug_sql(&sql, &args))
+    }
+
     fn py_to_sql(&self, py: &str, arg: &serde_json::Value) -> Result<(String, Vec<serde_json::Value>), crate::core::Error> {
         let nodes = self.py.parse_and_cache(py)?;
         let mut arg_array = vec![];
@@ -469,4 +670,104 @@ impl Rbatis {
         let (sql, args) = self.py_to_sql(py, &json)?;
         return self.fetch_page::<T>(tx_id, sql.as_str(), &args, page).await;
     }
-}
\ No newline at end of file
+}
+/// the next backoff of retry,backoff * 2 but not more than max_backoff
+fn next_retry_backoff(backoff: Duration, max_backoff: Duration) -> Duration {
+    (backoff * 2).min(max_backoff)
+}
+
+/// random sleep time in [backoff/2,backoff],avoid the conflicted tx retry at the same time
+fn retry_jitter(backoff: Duration) -> Duration {
+    let half = backoff / 2;
+    let nanos = (backoff - half).as_nanos() as u64;
+    if nanos == 0 {
+        return backoff;
+    }
+    let random = uuid::Uuid::new_v4().as_u128() as u64;
+    half + Duration::from_nanos(random % (nanos + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn deadlock_error() -> crate::core::Error {
+        crate::core::Error::Database {
+            driver_type: DriverType::Postgres,
+            code: Some("40P01".to_string()),
+            constraint: None,
+            message: "deadlock detected".to_string(),
+        }
+    }
+
+    fn count_attempts(max_attempts: u32, error: crate::core::Error) -> u32 {
+        let mut rb = Rbatis::new();
+        rb.retry_max_attempts = max_attempts;
+        rb.retry_backoff = Duration::from_millis(1);
+        let attempts = &AtomicU32::new(0);
+        let error = &error;
+        let result: Result<(), crate::core::Error> = async_std::task::block_on(rb.retry(|| async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(error.clone())
+        }));
+        assert!(result.is_err());
+        attempts.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_retry_attempts() {
+        assert_eq!(count_attempts(0, deadlock_error()), 1);
+        assert_eq!(count_attempts(1, deadlock_error()), 1);
+        assert_eq!(count_attempts(3, deadlock_error()), 3);
+        assert_eq!(count_attempts(3, crate::core::Error::from("not retryable")), 1);
+    }
+
+    #[test]
+    fn test_retry_until_ok() {
+        let mut rb = Rbatis::new();
+        rb.retry_max_attempts = 3;
+        rb.retry_backoff = Duration::from_millis(1);
+        let attempts = &AtomicU32::new(0);
+        let result = async_std::task::block_on(rb.retry(|| async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(deadlock_error());
+            }
+            Ok(1)
+        }));
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let max = Duration::from_millis(300);
+        let mut backoff = Duration::from_millis(50);
+        let mut backoffs = vec![];
+        for _ in 0..4 {
+            backoff = next_retry_backoff(backoff, max);
+            backoffs.push(backoff.as_millis());
+        }
+        assert_eq!(backoffs, vec![100, 200, 300, 300]);
+        for _ in 0..100 {
+            let sleep = retry_jitter(Duration::from_millis(100));
+            assert!(sleep >= Duration::from_millis(50) && sleep <= Duration::from_millis(100));
+        }
+        assert_eq!(retry_jitter(Duration::from_nanos(0)), Duration::from_nanos(0));
+    }
+
+    #[test]
+    fn test_transaction_retry_begin_fail() {
+        let mut rb = Rbatis::new();
+        rb.retry_max_attempts = 3;
+        let attempts = &AtomicU32::new(0);
+        //no pool,begin fail and the closure not run
+        let result: Result<(), crate::core::Error> = async_std::task::block_on(rb.transaction_retry(|_tx_id| async move {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }));
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+    }
+}
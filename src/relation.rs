@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::core::Error;
+use crate::core::Result;
+use crate::crud::{CRUD, CRUDEnable, make_columns};
+use crate::rbatis::Rbatis;
+use crate::wrapper::Wrapper;
+
+/// load the relation of tables, every relation only use one IN sql per chunk(not N+1 sql).
+/// the logic_plugin also filter the deleted rows.
+#[async_trait]
+pub trait Relation {
+    /// one-to-many, load children by child's foreign_key column = parent id,for Example:
+    ///
+    /// rb.load_children::<BizActivity, BizActivityImg, _>("", &mut activities, "activity_id", |a, imgs| a.imgs = Some(imgs)).await?;
+    /// [rbatis] Query ==> SELECT activity_id,id,url FROM biz_activity_img WHERE activity_id IN ( ? , ? )
+    ///
+    /// notice: the parents must be unique by id, every parent will call set(even no children)
+    async fn load_children<P, C, F>(&self, tx_id: &str, parents: &mut [P], foreign_key: &str, set: F) -> Result<()>
+        where P: CRUDEnable, C: CRUDEnable, F: Fn(&mut P, Vec<C>) + Send + Sync;
+
+    /// many-to-one, load parent by parent id = child's foreign_key column,for Example:
+    ///
+    /// rb.load_parent::<BizActivityImg, BizActivity, _>("", &mut imgs, "activity_id", |img, a| img.activity = a).await?;
+    /// [rbatis] Query ==> SELECT id,name FROM biz_activity WHERE id IN ( ? , ? )
+    async fn load_parent<C, P, F>(&self, tx_id: &str, children: &mut [C], foreign_key: &str, set: F) -> Result<()>
+        where C: CRUDEnable, P: CRUDEnable + Clone, F: Fn(&mut C, Option<P>) + Send + Sync;
+}
+
+#[async_trait]
+impl Relation for Rbatis {
+    async fn load_children<P, C, F>(&self, tx_id: &str, parents: &mut [P], foreign_key: &str, set: F) -> Result<()>
+        where P: CRUDEnable, C: CRUDEnable, F: Fn(&mut P, Vec<C>) + Send + Sync {
+        if parents.is_empty() {
+            return Ok(());
+        }
+        make_columns::<C>(&[foreign_key])?;
+        let ids = collect_column_values(parents, &P::id_name())?;
+        let children: Vec<C> = self.list_by_in::<C>(tx_id, foreign_key, &ids).await?;
+        let mut groups = group_by_column(children, foreign_key)?;
+        for parent in parents.iter_mut() {
+            let key = value_key(&column_value(parent, &P::id_name())?);
+            set(parent, groups.remove(&key).unwrap_or(vec![]));
+        }
+        Ok(())
+    }
+
+    async fn load_parent<C, P, F>(&self, tx_id: &str, children: &mut [C], foreign_key: &str, set: F) -> Result<()>
+        where C: CRUDEnable, P: CRUDEnable + Clone, F: Fn(&mut C, Option<P>) + Send + Sync {
+        if children.is_empty() {
+            return Ok(());
+        }
+        make_columns::<C>(&[foreign_key])?;
+        let ids = collect_column_values(children, foreign_key)?;
+        let parents: Vec<P> = self.list_by_in::<P>(tx_id, &P::id_name(), &ids).await?;
+        let groups = group_by_column(parents, &P::id_name())?;
+        for child in children.iter_mut() {
+            let key = value_key(&column_value(child, foreign_key)?);
+            let parent = groups.get(&key).and_then(|x| x.first().cloned());
+            set(child, parent);
+        }
+        Ok(())
+    }
+}
+
+impl Rbatis {
+    /// list T by column IN values, split values into chunks by DriverType::max_bind_params()
+    async fn list_by_in<T>(&self, tx_id: &str, column: &str, values: &[Value]) -> Result<Vec<T>> where T: CRUDEnable {
+        let mut result = vec![];
+        if values.is_empty() {
+            return Ok(result);
+        }
+        let driver_type = self.driver_type()?;
+        for chunk in values.chunks(driver_type.max_bind_params().max(1)) {
+            let w = Wrapper::new(&driver_type).in_array(column, chunk).check()?;
+            let mut list: Vec<T> = self.list_by_wrapper(tx_id, &w).await?;
+            result.append(&mut list);
+        }
+        Ok(result)
+    }
+}
+
+/// the map key of id/foreign key value, so 1 and "1" is the same key
+fn value_key(v: &Value) -> String {
+    match v {
+        Value::String(s) => s.to_string(),
+        _ => v.to_string()
+    }
+}
+
+fn column_value<T>(arg: &T, column: &str) -> Result<Value> where T: Serialize {
+    let v = json!(arg);
+    if !v.is_object() {
+        return Err(Error::from("[rbatis] relation arg must be an object/struct!"));
+    }
+    Ok(v.get(column).cloned().unwrap_or(Value::Null))
+}
+
+/// collect the not null and unique column values
+fn collect_column_values<T>(args: &[T], column: &str) -> Result<Vec<Value>> where T: Serialize {
+    let mut keys = HashSet::new();
+    let mut values = vec![];
+    for x in args {
+        let v = column_value(x, column)?;
+        if v.is_null() {
+            continue;
+        }
+        if keys.insert(value_key(&v)) {
+            values.push(v);
+        }
+    }
+    Ok(values)
+}
+
+fn group_by_column<T>(args: Vec<T>, column: &str) -> Result<HashMap<String, Vec<T>>> where T: Serialize {
+    let mut groups: HashMap<String, Vec<T>> = HashMap::new();
+    for x in args {
+        let v = column_value(&x, column)?;
+        if v.is_null() {
+            continue;
+        }
+        groups.entry(value_key(&v)).or_insert(vec![]).push(x);
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod test {
+    use serde::{Deserialize, Serialize};
+
+    use crate::relation::{collect_column_values, group_by_column};
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct BizActivityImg {
+        pub id: Option<i32>,
+        pub activity_id: Option<String>,
+    }
+
+    #[test]
+    fn test_group_by_column() {
+        let imgs = vec![
+            BizActivityImg { id: Some(1), activity_id: Some("1".to_string()) },
+            BizActivityImg { id: Some(2), activity_id: Some("1".to_string()) },
+            BizActivityImg { id: Some(3), activity_id: Some("2".to_string()) },
+            BizActivityImg { id: Some(4), activity_id: None },
+        ];
+        let ids = collect_column_values(&imgs, "activity_id").unwrap();
+        assert_eq!(ids, vec![json!("1"), json!("2")]);
+        let groups = group_by_column(imgs, "activity_id").unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups.get("1").unwrap().len(), 2);
+        assert_eq!(groups.get("2").unwrap()[0].id, Some(3));
+    }
+}
@@ -0,0 +1,133 @@
+use serde_json::Value;
+
+use crate::core::db::DriverType;
+use crate::sql::DebugSql;
+
+impl DebugSql for DriverType {
+    fn debug_literal(&self, value: &Value) -> String {
+        match value {
+            Value::Null => "NULL".to_string(),
+            Value::Bool(b) => {
+                match self {
+                    DriverType::Postgres => b.to_string(),
+                    _ => {
+                        if *b { "1".to_string() } else { "0".to_string() }
+                    }
+                }
+            }
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => {
+                if is_date_time(s) && !self.eq(&DriverType::Sqlite) {
+                    //2020-01-01T00:00:00 => '2020-01-01 00:00:00'
+                    return quote_string(self, &s.replacen("T", " ", 1));
+                }
+                quote_string(self, s)
+            }
+            //array/object save as json string
+            _ => quote_string(self, &value.to_string())
+        }
+    }
+
+    fn debug_sql(&self, sql: &str, args: &[Value]) -> String {
+        let mut result = String::with_capacity(sql.len());
+        let mut chars = sql.chars().peekable();
+        let mut in_string = false;
+        let mut index = 0;
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                in_string = !in_string;
+                result.push(c);
+                continue;
+            }
+            if in_string {
+                result.push(c);
+                continue;
+            }
+            match self {
+                DriverType::Postgres => {
+                    if c == '$' {
+                        let mut num = String::new();
+                        while let Some(d) = chars.peek() {
+                            if !d.is_ascii_digit() {
+                                break;
+                            }
+                            num.push(*d);
+                            chars.next();
+                        }
+                        let arg = num.parse::<usize>().ok()
+                            .and_then(|n| if n > 0 { args.get(n - 1) } else { None });
+                        match arg {
+                            Some(arg) => result.push_str(&self.debug_literal(arg)),
+                            None => {
+                                result.push(c);
+                                result.push_str(&num);
+                            }
+                        }
+                        continue;
+                    }
+                }
+                _ => {
+                    if c == '?' {
+                        match args.get(index) {
+                            Some(arg) => result.push_str(&self.debug_literal(arg)),
+                            None => result.push(c)
+                        }
+                        index += 1;
+                        continue;
+                    }
+                }
+            }
+            result.push(c);
+        }
+        result
+    }
+}
+
+fn quote_string(driver_type: &DriverType, s: &str) -> String {
+    let mut escaped = s.replace("'", "''");
+    match driver_type {
+        DriverType::Mysql => {
+            escaped = escaped.replace("\\", "\\\\");
+            format!("'{}'", escaped)
+        }
+        DriverType::Mssql => format!("N'{}'", escaped),
+        _ => format!("'{}'", escaped)
+    }
+}
+
+/// is like "2020-01-01T00:00:00" or "2020-01-01T00:00:00.000"
+fn is_date_time(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if bytes.len() < 19 {
+        return false;
+    }
+    for (i, b) in bytes[..19].iter().enumerate() {
+        let ok = match i {
+            4 | 7 => *b == b'-',
+            10 => *b == b'T',
+            13 | 16 => *b == b':',
+            _ => b.is_ascii_digit()
+        };
+        if !ok {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::db::DriverType;
+    use crate::sql::DebugSql;
+
+    #[test]
+    fn test_debug_sql() {
+        let args = vec![json!("a'b"), json!(1), json!(true), serde_json::Value::Null, json!("2020-01-01T10:00:00")];
+        assert_eq!(DriverType::Mysql.debug_sql("SELECT * FROM t WHERE name = ? AND id = ? AND ok = ? AND d IS ? AND c = ? AND x = '?'", &args),
+                   "SELECT * FROM t WHERE name = 'a''b' AND id = 1 AND ok = 1 AND d IS NULL AND c = '2020-01-01 10:00:00' AND x = '?'");
+        assert_eq!(DriverType::Postgres.debug_sql("SELECT * FROM t WHERE name = $1 AND id = $2 AND ok = $3 AND c = $5::timestamp", &args),
+                   "SELECT * FROM t WHERE name = 'a''b' AND id = 1 AND ok = true AND c = '2020-01-01 10:00:00'::timestamp");
+        assert_eq!(DriverType::Mssql.debug_sql("SELECT * FROM t WHERE name = ?", &args), "SELECT * FROM t WHERE name = N'a''b'");
+        assert_eq!(DriverType::Sqlite.debug_sql("SELECT * FROM t WHERE id = ? AND x = ?", &args[1..2]), "SELECT * FROM t WHERE id = 1 AND x = ?");
+    }
+}
@@ -0,0 +1,100 @@
+use crate::core::db::DriverType;
+use crate::sql::IdentQuote;
+
+impl IdentQuote for DriverType {
+    fn quote_ident(&self, ident: &str) -> String {
+        let (left, right) = match self {
+            DriverType::Mysql => ("`", "`"),
+            DriverType::Mssql => ("[", "]"),
+            DriverType::Postgres | DriverType::Sqlite => ("\"", "\""),
+            _ => ("", "")
+        };
+        let mut quoted = vec![];
+        for segment in ident.split(".") {
+            quoted.push(format!("{}{}{}", left, segment, right));
+        }
+        quoted.join(".")
+    }
+}
+
+/// identifier must be [A-Za-z0-9_] segments, split by '.'
+/// for example: "name", "t.name"
+pub fn is_valid_ident(ident: &str) -> bool {
+    if ident.is_empty() {
+        return false;
+    }
+    for segment in ident.split(".") {
+        if segment.is_empty() || !segment.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return false;
+        }
+    }
+    true
+}
+
+/// the raw sql fragment(having sql,set sql) can not contains ';','--','/*'
+pub fn is_safe_sql_fragment(sql: &str) -> bool {
+    !sql.contains(";") && !sql.contains("--") && !sql.contains("/*")
+}
+
+/// numeric literal,for example: "1", "-1", "1.5"
+pub fn is_numeric_literal(arg: &str) -> bool {
+    let digits = arg.strip_prefix("-").unwrap_or(arg);
+    if digits.is_empty() || digits.starts_with(".") || digits.ends_with(".") {
+        return false;
+    }
+    digits.chars().all(|c| c.is_ascii_digit() || c == '.') && digits.matches('.').count() <= 1
+}
+
+/// the value of ${}: identifiers or numbers split by ',' and ' ',for example: "biz_activity", "create_time desc,id asc", "10"
+pub fn is_safe_ident_list(arg: &str) -> bool {
+    if !is_safe_sql_fragment(arg) {
+        return false;
+    }
+    if arg.trim().is_empty() {
+        return true;
+    }
+    arg.split(",").all(|item| {
+        let mut tokens = item.split_whitespace().peekable();
+        tokens.peek().is_some() && tokens.all(|token| is_valid_ident(token) || is_numeric_literal(token))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::db::DriverType;
+    use crate::sql::ident::{is_numeric_literal, is_safe_ident_list, is_safe_sql_fragment, is_valid_ident};
+    use crate::sql::IdentQuote;
+
+    #[test]
+    fn test_ident() {
+        assert!(is_valid_ident("name"));
+        assert!(is_valid_ident("t.create_time"));
+        assert!(!is_valid_ident("name desc"));
+        assert!(!is_valid_ident("name;drop table t"));
+        assert!(!is_valid_ident("t."));
+        assert!(!is_valid_ident(""));
+
+        assert_eq!(DriverType::Mysql.quote_ident("t.name"), "`t`.`name`");
+        assert_eq!(DriverType::Postgres.quote_ident("name"), "\"name\"");
+        assert_eq!(DriverType::Mssql.quote_ident("name"), "[name]");
+
+        assert!(is_safe_sql_fragment("count(1) > 1"));
+        assert!(!is_safe_sql_fragment("1 = 1; drop table t"));
+        assert!(!is_safe_sql_fragment("1 = 1 --"));
+        assert!(!is_safe_sql_fragment("1 = 1 /* */"));
+
+        assert!(is_numeric_literal("1"));
+        assert!(is_numeric_literal("-1.5"));
+        assert!(!is_numeric_literal("1 OR 1=1"));
+        assert!(!is_numeric_literal("1.2.3"));
+
+        assert!(is_safe_ident_list("create_time desc"));
+        assert!(is_safe_ident_list("t.create_time DESC, id ASC"));
+        assert!(is_safe_ident_list("John Doe"));
+        assert!(!is_safe_ident_list("id,"));
+        assert!(!is_safe_ident_list("id desc; drop table t"));
+        assert!(!is_safe_ident_list("(select 1)"));
+        assert!(!is_safe_ident_list("1=1"));
+        assert!(!is_numeric_literal("-"));
+    }
+}
@@ -4,8 +4,47 @@ pub mod limit;
 
 pub mod date;
 
+pub mod upsert;
+
+pub mod ident;
+
+pub mod debug;
+
+pub mod savepoint;
+
 
 pub trait PageLimit {
     /// return  sql
     fn page_limit_sql(&self, offset: u64, size: u64) -> crate::core::Result<String>;
-}
\ No newline at end of file
+}
+
+pub trait Upsert {
+    /// return insert or update sql
+    /// columns: for example: "id,name,version"
+    /// values_sql: for example: "( ? , ? , ? ),( ? , ? , ? )"
+    /// conflict_columns: the primary key/unique columns, for example: ["id"]
+    fn upsert_sql(&self, table_name: &str, columns: &str, values_sql: &str, conflict_columns: &[String]) -> crate::core::Result<String>;
+}
+
+pub trait IdentQuote {
+    /// return quoted identifier(table/column name)
+    /// for example: mysql `name`,postgres/sqlite "name",mssql [name]. "t.name" => `t`.`name`
+    fn quote_ident(&self, ident: &str) -> String;
+}
+
+pub trait Savepoint {
+    /// return create savepoint sql
+    fn savepoint_sql(&self, name: &str) -> crate::core::Result<String>;
+    /// return release savepoint sql,return empty if not need release(mssql)
+    fn release_savepoint_sql(&self, name: &str) -> crate::core::Result<String>;
+    /// return rollback to savepoint sql
+    fn rollback_to_savepoint_sql(&self, name: &str) -> crate::core::Result<String>;
+}
+
+/// debug only! render sql with inlined args, the result can not used to execute
+pub trait DebugSql {
+    /// return the sql literal of value,for example: 'a''b', NULL, 1
+    fn debug_literal(&self, value: &Value) -> String;
+    /// replace the placeholder('?' or '$1') with debug_literal(arg)
+    fn debug_sql(&self, sql: &str, args: &[Value]) -> String;
+}
@@ -0,0 +1,56 @@
+use crate::core::db::DriverType;
+use crate::sql::Savepoint;
+
+impl Savepoint for DriverType {
+    fn savepoint_sql(&self, name: &str) -> crate::core::Result<String> {
+        return match self {
+            DriverType::Mysql | DriverType::Postgres | DriverType::Sqlite => {
+                Ok(format!("SAVEPOINT {}", name))
+            }
+            DriverType::Mssql => {
+                Ok(format!("SAVE TRANSACTION {}", name))
+            }
+            DriverType::None => {
+                Err(crate::core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+
+    fn release_savepoint_sql(&self, name: &str) -> crate::core::Result<String> {
+        return match self {
+            DriverType::Mysql | DriverType::Postgres | DriverType::Sqlite => {
+                Ok(format!("RELEASE SAVEPOINT {}", name))
+            }
+            DriverType::Mssql => {
+                //sqlserver not need release
+                Ok(String::new())
+            }
+            DriverType::None => {
+                Err(crate::core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+
+    fn rollback_to_savepoint_sql(&self, name: &str) -> crate::core::Result<String> {
+        return match self {
+            DriverType::Mysql | DriverType::Postgres | DriverType::Sqlite => {
+                Ok(format!("ROLLBACK TO SAVEPOINT {}", name))
+            }
+            DriverType::Mssql => {
+                Ok(format!("ROLLBACK TRANSACTION {}", name))
+            }
+            DriverType::None => {
+                Err(crate::core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+}
+
+#[test]
+pub fn test_savepoint() {
+    assert_eq!(DriverType::Mysql.savepoint_sql("sp_1").unwrap(), "SAVEPOINT sp_1");
+    assert_eq!(DriverType::Postgres.rollback_to_savepoint_sql("sp_1").unwrap(), "ROLLBACK TO SAVEPOINT sp_1");
+    assert_eq!(DriverType::Mssql.savepoint_sql("sp_1").unwrap(), "SAVE TRANSACTION sp_1");
+    assert_eq!(DriverType::Mssql.release_savepoint_sql("sp_1").unwrap(), "");
+    assert!(DriverType::None.savepoint_sql("sp_1").is_err());
+}
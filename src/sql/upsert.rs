@@ -0,0 +1,90 @@
+use crate::core::db::DriverType;
+use crate::core::Error;
+use crate::sql::Upsert;
+
+impl Upsert for DriverType {
+    fn upsert_sql(&self, table_name: &str, columns: &str, values_sql: &str, conflict_columns: &[String]) -> crate::core::Result<String> {
+        if conflict_columns.is_empty() {
+            return Err(Error::from("[rbatis] upsert sql must have conflict columns!"));
+        }
+        let column_vec: Vec<&str> = columns.split(",").map(|x| x.trim()).collect();
+        let mut update_columns = vec![];
+        for column in &column_vec {
+            if !conflict_columns.iter().any(|x| x.eq(column)) {
+                update_columns.push(*column);
+            }
+        }
+        return match self {
+            DriverType::Mysql => {
+                let mut sets = String::new();
+                for column in &update_columns {
+                    sets.push_str(format!("{} = VALUES({}),", column, column).as_str());
+                }
+                if sets.is_empty() {
+                    //nothing to update, keep the row
+                    sets = format!("{} = {}", conflict_columns[0], conflict_columns[0]);
+                } else {
+                    sets.pop();
+                }
+                Ok(format!("INSERT INTO {} ({}) VALUES {} ON DUPLICATE KEY UPDATE {}", table_name, columns, values_sql, sets))
+            }
+            DriverType::Postgres | DriverType::Sqlite => {
+                let mut sets = String::new();
+                for column in &update_columns {
+                    sets.push_str(format!("{} = excluded.{},", column, column).as_str());
+                }
+                let action;
+                if sets.is_empty() {
+                    action = "DO NOTHING".to_string();
+                } else {
+                    sets.pop();
+                    action = format!("DO UPDATE SET {}", sets);
+                }
+                Ok(format!("INSERT INTO {} ({}) VALUES {} ON CONFLICT ({}) {}", table_name, columns, values_sql, conflict_columns.join(","), action))
+            }
+            DriverType::Mssql => {
+                //sqlserver
+                let mut on = String::new();
+                for column in conflict_columns {
+                    on.push_str(format!("target.{} = source.{} AND ", column, column).as_str());
+                }
+                on = on.trim_end_matches(" AND ").to_string();
+                let mut sets = String::new();
+                for column in &update_columns {
+                    sets.push_str(format!("target.{} = source.{},", column, column).as_str());
+                }
+                sets.pop();
+                let mut source_values = String::new();
+                for column in &column_vec {
+                    source_values.push_str(format!("source.{},", column).as_str());
+                }
+                source_values.pop();
+                let mut sql = format!("MERGE INTO {} AS target USING (VALUES {}) AS source ({}) ON {}", table_name, values_sql, columns, on);
+                if !sets.is_empty() {
+                    sql.push_str(format!(" WHEN MATCHED THEN UPDATE SET {}", sets).as_str());
+                }
+                sql.push_str(format!(" WHEN NOT MATCHED THEN INSERT ({}) VALUES ({});", columns, source_values).as_str());
+                Ok(sql)
+            }
+            DriverType::None => {
+                Err(crate::core::Error::from(format!("[rbatis] not support now for DriverType:{:?}", DriverType::None)))
+            }
+        };
+    }
+}
+
+#[test]
+pub fn test_upsert_sql() {
+    let conflict = vec!["id".to_string()];
+    let mysql = DriverType::Mysql.upsert_sql("biz_activity", "id,name,version", "(?,?,?)", &conflict).unwrap();
+    println!("{}", mysql);
+    assert_eq!(mysql, "INSERT INTO biz_activity (id,name,version) VALUES (?,?,?) ON DUPLICATE KEY UPDATE name = VALUES(name),version = VALUES(version)");
+    let pg = DriverType::Postgres.upsert_sql("biz_activity", "id,name,version", "($1,$2,$3)", &conflict).unwrap();
+    println!("{}", pg);
+    assert_eq!(pg, "INSERT INTO biz_activity (id,name,version) VALUES ($1,$2,$3) ON CONFLICT (id) DO UPDATE SET name = excluded.name,version = excluded.version");
+    let mssql = DriverType::Mssql.upsert_sql("biz_activity", "id,name", "(?,?)", &conflict).unwrap();
+    println!("{}", mssql);
+    assert_eq!(mssql, "MERGE INTO biz_activity AS target USING (VALUES (?,?)) AS source (id,name) ON target.id = source.id WHEN MATCHED THEN UPDATE SET target.name = source.name WHEN NOT MATCHED THEN INSERT (id,name) VALUES (source.id,source.name);");
+    let sqlite = DriverType::Sqlite.upsert_sql("biz_activity", "id", "(?)", &conflict).unwrap();
+    assert_eq!(sqlite, "INSERT INTO biz_activity (id) VALUES (?) ON CONFLICT (id) DO NOTHING");
+}
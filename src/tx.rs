@@ -3,44 +3,123 @@ use std::ops::{Deref, DerefMut};
 use std::time::{Duration, Instant};
 
 use rbatis_core::db_adapter::DBPool;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
 
-use crate::core::db_adapter::DBTx;
+use crate::core::db::{DriverType, TxOptions};
+use crate::core::db_adapter::{DBExecResult, DBTx};
+use crate::crud::{CRUD, CRUDEnable};
 use crate::core::runtime::{Arc, Receiver, RwLock, RwLockReadGuard, Sender};
 use crate::core::sync::sync_map::{RefMut, SyncMap};
 use crate::plugin::log::LogPlugin;
+use crate::plugin::page::{IPageRequest, Page};
+use crate::plugin::tx_event::{TxEvent, TxEventKind, TxEventPlugin};
 use crate::rbatis::Rbatis;
+use crate::relation::Relation;
+use crate::sql::Savepoint;
+use crate::update_wrapper::UpdateWrapper;
+use crate::wrapper::Wrapper;
 
 ///the Transaction manager，It manages the life cycle of transactions and provides access across threads
-///every tx_check_interval check tx is out of time(tx_lock_wait_timeout).if out, rollback tx.
+///every tx_check_interval check tx is out of time(the timeout of begin_with(),default tx_lock_wait_timeout).if out, rollback tx.
 ///if tx manager will be drop, manager will rollback all of tx.
+///the tx_event_plugin will be called on begin/commit/rollback/timeout rollback
 pub struct TxManager {
     pub tx_context: SyncMap<String, (DBTx, TxState)>,
+    tx_stack: SyncMap<String, TxStack>,
     pub tx_lock_wait_timeout: Duration,
     pub tx_check_interval: Duration,
     alive: RwLock<bool>,
     close_sender: Sender<bool>,
     close_recv: Receiver<bool>,
     pub log_plugin: Option<Arc<Box<dyn LogPlugin>>>,
+    pub tx_event_plugin: Option<Arc<Box<dyn TxEventPlugin>>>,
 }
 
 
-pub enum TxState {
-    StateBegin(Instant),
-    StateFinish(Instant),
+pub struct TxState {
+    pub begin_time: Instant,
+    /// rollback if out of time
+    pub timeout: Duration,
+    /// the last sql exec in tx
+    pub last_sql: String,
+}
+
+impl TxState {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            begin_time: Instant::now(),
+            timeout,
+            last_sql: String::new(),
+        }
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.begin_time.elapsed() > self.timeout
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TxStatus {
+    Active,
+    /// the inner Required tx rollback,the tx can only rollback
+    RollbackOnly,
+}
+
+/// the tx info of TxManager::list()
+#[derive(Clone, Debug)]
+pub struct TxInfo {
+    pub tx_id: String,
+    /// the time from begin
+    pub age: Duration,
+    pub timeout: Duration,
+    pub status: TxStatus,
+    /// the count of inner begin_propagation()
+    pub depth: usize,
+    pub last_sql: String,
+}
+
+/// the propagation of begin tx when the tx_id already begin
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Propagation {
+    /// join the exist tx,the inner commit do nothing,the inner rollback mark the tx rollback only
+    Required,
+    /// suspend the exist tx and begin a new tx(new conn),the exist tx resume after inner commit/rollback
+    RequiresNew,
+    /// create a savepoint on the exist tx,the inner commit release it,the inner rollback rollback to it
+    Nested,
+}
+
+/// the inner begin of a tx_id
+enum TxFrame {
+    Required,
+    /// the suspended tx and it's rollback_only
+    RequiresNew(DBTx, TxState, bool),
+    /// savepoint name
+    Nested(String),
+}
+
+#[derive(Default)]
+struct TxStack {
+    frames: Vec<TxFrame>,
+    rollback_only: bool,
 }
 
 
 impl TxManager {
-    pub fn new_arc(plugin: Arc<Box<dyn LogPlugin>>, tx_lock_wait_timeout: Duration, tx_check_interval: Duration) -> Arc<Self> {
+    pub fn new_arc(plugin: Arc<Box<dyn LogPlugin>>, tx_event_plugin: Option<Arc<Box<dyn TxEventPlugin>>>, tx_lock_wait_timeout: Duration, tx_check_interval: Duration) -> Arc<Self> {
         let (s, r) = crate::core::runtime::channel(1);
         let s = Self {
             tx_context: SyncMap::new(),
+            tx_stack: SyncMap::new(),
             tx_lock_wait_timeout,
             tx_check_interval,
             alive: RwLock::new(false),
             close_sender: s,
             close_recv: r,
             log_plugin: Some(plugin),
+            tx_event_plugin,
         };
         let arc = Arc::new(s);
         TxManager::polling_check(&arc.clone());
@@ -77,6 +156,26 @@ impl TxManager {
         }
     }
 
+    fn fire_event(&self, tx_id: &str, kind: TxEventKind, state: &TxState, savepoint: Option<&str>, success: bool) {
+        match &self.tx_event_plugin {
+            Some(v) => {
+                v.on_event(&TxEvent {
+                    tx_id,
+                    kind,
+                    age: state.begin_time.elapsed(),
+                    savepoint,
+                    success,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// the timeout of tx begin with options
+    fn tx_timeout(&self, options: &TxOptions) -> Duration {
+        options.timeout.unwrap_or(self.tx_lock_wait_timeout)
+    }
+
     ///polling check tx alive
     fn polling_check(manager: &Arc<TxManager>) {
         let is_alive = crate::core::runtime::block_on(async {
@@ -100,7 +199,7 @@ impl TxManager {
                         if manager.is_enable_log() {
                             manager.do_log(&format!("[rbatis] rollback tx_id:{},Because the manager exits", tx_id));
                         }
-                        manager.rollback(tx_id).await;
+                        manager.force_rollback(tx_id, TxEventKind::Rollback).await;
                     }
                     //notice close
                     manager.close_sender.send(true);
@@ -109,32 +208,26 @@ impl TxManager {
                 let m = manager.tx_context.read().await;
                 let mut need_rollback = None;
                 for (k, (tx, state)) in m.deref() {
-                    match state {
-                        TxState::StateBegin(instant) => {
-                            let out_time = instant.elapsed();
-                            if out_time > manager.tx_lock_wait_timeout {
-                                if need_rollback == None {
-                                    need_rollback = Some(vec![]);
-                                }
-                                match &mut need_rollback {
-                                    Some(v) => {
-                                        v.push(k.to_string());
-                                    }
-                                    _ => {}
-                                }
+                    if state.is_timeout() {
+                        if need_rollback == None {
+                            need_rollback = Some(vec![]);
+                        }
+                        match &mut need_rollback {
+                            Some(v) => {
+                                v.push((k.to_string(), state.timeout));
                             }
+                            _ => {}
                         }
-                        _ => {}
                     }
                 }
                 drop(m);
                 match &mut need_rollback {
                     Some(v) => {
-                        for tx_id in v {
+                        for (tx_id, timeout) in v {
                             if manager.is_enable_log() {
-                                manager.do_log(&format!("[rbatis] rollback tx_id:{},out of time:{:?}", tx_id, &manager.tx_lock_wait_timeout));
+                                manager.do_log(&format!("[rbatis] rollback tx_id:{},out of time:{:?}", tx_id, timeout));
                             }
-                            manager.rollback(tx_id).await;
+                            manager.force_rollback(tx_id, TxEventKind::TimeoutRollback).await;
                         }
                         //shrink_to_fit
                         manager.tx_context.shrink_to_fit().await;
@@ -151,36 +244,498 @@ impl TxManager {
         self.tx_context.get_mut(tx_id).await
     }
 
+    /// list the info of all tx,for example find leaked tx
+    pub async fn list(&self) -> Vec<TxInfo> {
+        let m = self.tx_context.read().await;
+        let stacks = self.tx_stack.read().await;
+        let mut result = Vec::with_capacity(m.len());
+        for (k, (_, state)) in m.deref() {
+            let (depth, rollback_only) = match stacks.get(k) {
+                Some(stack) => (stack.frames.len(), stack.rollback_only),
+                None => (0, false)
+            };
+            result.push(TxInfo {
+                tx_id: k.to_string(),
+                age: state.begin_time.elapsed(),
+                timeout: state.timeout,
+                status: if rollback_only { TxStatus::RollbackOnly } else { TxStatus::Active },
+                depth,
+                last_sql: state.last_sql.clone(),
+            });
+        }
+        result
+    }
+
     /// begin tx,for new conn
     pub async fn begin(&self, new_tx_id: &str, pool: &DBPool) -> Result<u64, crate::core::Error> {
         if new_tx_id.is_empty() {
             return Err(crate::core::Error::from("[rbatis] tx_id can not be empty"));
         }
         let conn: DBTx = pool.begin().await?;
+        let state = TxState::new(self.tx_lock_wait_timeout);
+        self.fire_event(new_tx_id, TxEventKind::Begin, &state, None, true);
         //send tx to context
-        self.tx_context.insert(new_tx_id.to_string(), (conn, TxState::StateBegin(Instant::now()))).await;
+        self.tx_context.insert(new_tx_id.to_string(), (conn, state)).await;
+        return Ok(1);
+    }
+
+    /// begin tx with options,for new conn
+    pub async fn begin_with(&self, new_tx_id: &str, pool: &DBPool, options: &TxOptions) -> Result<u64, crate::core::Error> {
+        if new_tx_id.is_empty() {
+            return Err(crate::core::Error::from("[rbatis] tx_id can not be empty"));
+        }
+        let conn: DBTx = pool.begin_with(options).await?;
+        let state = TxState::new(self.tx_timeout(options));
+        self.fire_event(new_tx_id, TxEventKind::Begin, &state, None, true);
+        //send tx to context
+        self.tx_context.insert(new_tx_id.to_string(), (conn, state)).await;
+        return Ok(1);
+    }
+
+    /// begin tx with propagation,if the tx_id not begin,it is same as begin().
+    /// every begin_propagation() must match one commit()/rollback()
+    pub async fn begin_propagation(&self, tx_id: &str, pool: &DBPool, propagation: Propagation) -> Result<u64, crate::core::Error> {
+        if tx_id.is_empty() {
+            return Err(crate::core::Error::from("[rbatis] tx_id can not be empty"));
+        }
+        if self.tx_context.get(tx_id).await.is_none() {
+            return self.begin(tx_id, pool).await;
+        }
+        match propagation {
+            Propagation::Required => {
+                self.push_frame(tx_id, TxFrame::Required).await;
+            }
+            Propagation::RequiresNew => {
+                let conn: DBTx = pool.begin().await?;
+                let state = TxState::new(self.tx_lock_wait_timeout);
+                self.fire_event(tx_id, TxEventKind::Begin, &state, None, true);
+                let old = self.tx_context.insert(tx_id.to_string(), (conn, state)).await;
+                match old {
+                    Some((old_tx, old_state)) => {
+                        let mut m = self.tx_stack.write().await;
+                        let stack = m.entry(tx_id.to_string()).or_insert_with(TxStack::default);
+                        let rollback_only = std::mem::replace(&mut stack.rollback_only, false);
+                        stack.frames.push(TxFrame::RequiresNew(old_tx, old_state, rollback_only));
+                    }
+                    None => {
+                        //the exist tx is finish by other,so it is a new tx
+                    }
+                }
+            }
+            Propagation::Nested => {
+                let name = format!("rbatis_sp_{}", self.frame_len(tx_id).await + 1);
+                self.exec_savepoint_sql(tx_id, TxEventKind::Begin, &name, |driver_type| driver_type.savepoint_sql(&name)).await?;
+                self.push_frame(tx_id, TxFrame::Nested(name)).await;
+            }
+        }
         return Ok(1);
     }
 
     /// commit tx,and return conn
     pub async fn commit(&self, tx_id: &str) -> Result<u64, crate::core::Error> {
-        let tx_op = self.tx_context.remove(tx_id).await;
-        if tx_op.is_none() {
-            return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+        let (frame, rollback_only) = self.pop_frame(tx_id).await;
+        match frame {
+            Some(TxFrame::Required) => {
+                //commit by the outer
+                return Ok(1);
+            }
+            Some(TxFrame::Nested(name)) => {
+                self.exec_savepoint_sql(tx_id, TxEventKind::Commit, &name, |driver_type| driver_type.release_savepoint_sql(&name)).await?;
+                return Ok(1);
+            }
+            Some(TxFrame::RequiresNew(old_tx, old_state, _)) => {
+                let tx_op = self.tx_context.insert(tx_id.to_string(), (old_tx, old_state)).await;
+                if rollback_only {
+                    self.finish_tx(tx_id, tx_op, true).await?;
+                    return Err(crate::core::Error::from(format!("[rbatis] tx:{} is rollback only,because the inner tx rollback！", tx_id)));
+                }
+                return self.finish_tx(tx_id, tx_op, false).await;
+            }
+            None => {
+                let tx_op = self.tx_context.remove(tx_id).await;
+                if rollback_only {
+                    self.finish_tx(tx_id, tx_op, true).await?;
+                    return Err(crate::core::Error::from(format!("[rbatis] tx:{} is rollback only,because the inner tx rollback！", tx_id)));
+                }
+                return self.finish_tx(tx_id, tx_op, false).await;
+            }
         }
-        let (mut tx, state): (DBTx, TxState) = tx_op.unwrap();
-        let result = tx.commit().await?;
-        return Ok(1);
     }
 
     /// rollback tx,and return conn
     pub async fn rollback(&self, tx_id: &str) -> Result<u64, crate::core::Error> {
-        let tx_op = self.tx_context.remove(tx_id).await;
+        let (frame, _) = self.pop_frame(tx_id).await;
+        match frame {
+            Some(TxFrame::Required) => {
+                //rollback by the outer
+                let mut m = self.tx_stack.write().await;
+                m.entry(tx_id.to_string()).or_insert_with(TxStack::default).rollback_only = true;
+                return Ok(1);
+            }
+            Some(TxFrame::Nested(name)) => {
+                self.exec_savepoint_sql(tx_id, TxEventKind::Rollback, &name, |driver_type| driver_type.rollback_to_savepoint_sql(&name)).await?;
+                return Ok(1);
+            }
+            Some(TxFrame::RequiresNew(old_tx, old_state, _)) => {
+                let tx_op = self.tx_context.insert(tx_id.to_string(), (old_tx, old_state)).await;
+                return self.finish_tx(tx_id, tx_op, true).await;
+            }
+            None => {
+                let tx_op = self.tx_context.remove(tx_id).await;
+                return self.finish_tx(tx_id, tx_op, true).await;
+            }
+        }
+    }
+
+    /// rollback the tx and all of suspended tx
+    async fn force_rollback(&self, tx_id: &str, kind: TxEventKind) {
+        let stack = self.tx_stack.remove(tx_id).await;
+        if let Some(stack) = stack {
+            for frame in stack.frames {
+                if let TxFrame::RequiresNew(tx, state, _) = frame {
+                    let result = tx.rollback().await;
+                    self.fire_event(tx_id, kind, &state, None, result.is_ok());
+                }
+            }
+        }
+        if let Some((tx, state)) = self.tx_context.remove(tx_id).await {
+            let result = tx.rollback().await;
+            self.fire_event(tx_id, kind, &state, None, result.is_ok());
+        }
+    }
+
+    async fn finish_tx(&self, tx_id: &str, tx_op: Option<(DBTx, TxState)>, rollback: bool) -> Result<u64, crate::core::Error> {
         if tx_op.is_none() {
             return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
         }
-        let (tx, state): (DBTx, TxState) = tx_op.unwrap();
-        let result = tx.rollback().await?;
+        let (mut tx, state): (DBTx, TxState) = tx_op.unwrap();
+        if rollback {
+            let result = tx.rollback().await;
+            self.fire_event(tx_id, TxEventKind::Rollback, &state, None, result.is_ok());
+            result?;
+        } else {
+            let result = tx.commit().await;
+            self.fire_event(tx_id, TxEventKind::Commit, &state, None, result.is_ok());
+            result?;
+        }
         return Ok(1);
     }
-}
\ No newline at end of file
+
+    /// exec the savepoint sql,and fire the event of savepoint
+    async fn exec_savepoint_sql<F>(&self, tx_id: &str, kind: TxEventKind, name: &str, make_sql: F) -> Result<(), crate::core::Error>
+        where F: Fn(&DriverType) -> Result<String, crate::core::Error> {
+        let conn = self.tx_context.get_mut(tx_id).await;
+        if conn.is_none() {
+            return Err(crate::core::Error::from(format!("[rbatis] tx:{} not exist！", tx_id)));
+        }
+        let mut conn = conn.unwrap();
+        let sql = make_sql(&conn.value().0.driver_type)?;
+        let mut result = Ok(());
+        if !sql.is_empty() {
+            self.do_log(&format!("[rbatis] [{}] Exec ==> {}", tx_id, sql));
+            result = conn.value_mut().0.execute(&sql).await.map(|_| ());
+        }
+        self.fire_event(tx_id, kind, &conn.value().1, Some(name), result.is_ok());
+        return result;
+    }
+
+    async fn push_frame(&self, tx_id: &str, frame: TxFrame) {
+        let mut m = self.tx_stack.write().await;
+        m.entry(tx_id.to_string()).or_insert_with(TxStack::default).frames.push(frame);
+    }
+
+    async fn frame_len(&self, tx_id: &str) -> usize {
+        let m = self.tx_stack.read().await;
+        m.get(tx_id).map(|stack| stack.frames.len()).unwrap_or(0)
+    }
+
+    /// pop the last frame and return the rollback_only of current tx.
+    /// if no frame,the stack will be remove
+    async fn pop_frame(&self, tx_id: &str) -> (Option<TxFrame>, bool) {
+        let mut m = self.tx_stack.write().await;
+        let (frame, rollback_only) = match m.get_mut(tx_id) {
+            Some(stack) => {
+                let rollback_only = stack.rollback_only;
+                let frame = stack.frames.pop();
+                if let Some(TxFrame::RequiresNew(_, _, parent_rollback_only)) = &frame {
+                    stack.rollback_only = *parent_rollback_only;
+                }
+                (frame, rollback_only)
+            }
+            None => {
+                return (None, false);
+            }
+        };
+        if frame.is_none() {
+            m.remove(tx_id);
+        }
+        (frame, rollback_only)
+    }
+}
+
+/// the tx guard,create by Rbatis::begin_defer().
+/// if the guard drop without commit()/rollback(),the tx will be rollback.
+///
+/// for Example:
+///         let tx = rb.begin_defer().await?;
+///         tx.save(&activity).await?;
+///         tx.commit().await?;
+///
+/// the other method of Rbatis(for example py_fetch_page) can use tx.tx_id()
+///         rb.py_fetch_page(tx.tx_id(), py, &arg, &page).await?;
+pub struct TxGuard<'a> {
+    pub rb: &'a Rbatis,
+    pub tx_id: String,
+    done: bool,
+}
+
+impl<'a> TxGuard<'a> {
+    pub fn new(rb: &'a Rbatis, tx_id: &str) -> Self {
+        Self {
+            rb,
+            tx_id: tx_id.to_string(),
+            done: false,
+        }
+    }
+
+    pub fn tx_id(&self) -> &str {
+        &self.tx_id
+    }
+
+    pub async fn commit(mut self) -> Result<u64, crate::core::Error> {
+        self.done = true;
+        self.rb.commit(&self.tx_id).await
+    }
+
+    pub async fn rollback(mut self) -> Result<u64, crate::core::Error> {
+        self.done = true;
+        self.rb.rollback(&self.tx_id).await
+    }
+
+    pub async fn fetch<T>(&self, sql: &str) -> Result<T, crate::core::Error>
+        where T: DeserializeOwned {
+        self.rb.fetch(&self.tx_id, sql).await
+    }
+
+    pub async fn exec(&self, sql: &str) -> Result<DBExecResult, crate::core::Error> {
+        self.rb.exec(&self.tx_id, sql).await
+    }
+
+    pub async fn fetch_prepare<T>(&self, sql: &str, args: &Vec<Value>) -> Result<T, crate::core::Error>
+        where T: DeserializeOwned {
+        self.rb.fetch_prepare(&self.tx_id, sql, args).await
+    }
+
+    pub async fn exec_prepare(&self, sql: &str, args: &Vec<Value>) -> Result<DBExecResult, crate::core::Error> {
+        self.rb.exec_prepare(&self.tx_id, sql, args).await
+    }
+
+    pub async fn fetch_prepare_wrapper<T>(&self, w: &Wrapper) -> Result<T, crate::core::Error>
+        where T: DeserializeOwned {
+        self.rb.fetch_prepare_wrapper(&self.tx_id, w).await
+    }
+
+    pub async fn exec_prepare_wrapper(&self, w: &Wrapper) -> Result<DBExecResult, crate::core::Error> {
+        self.rb.exec_prepare_wrapper(&self.tx_id, w).await
+    }
+
+    pub async fn py_fetch<T, Ser>(&self, py: &str, arg: &Ser) -> Result<T, crate::core::Error>
+        where T: DeserializeOwned,
+              Ser: Serialize + Send + Sync {
+        self.rb.py_fetch(&self.tx_id, py, arg).await
+    }
+
+    pub async fn py_exec<Ser>(&self, py: &str, arg: &Ser) -> Result<DBExecResult, crate::core::Error>
+        where Ser: Serialize + Send + Sync {
+        self.rb.py_exec(&self.tx_id, py, arg).await
+    }
+
+    pub async fn save<T>(&self, entity: &T) -> Result<DBExecResult, crate::core::Error> where T: CRUDEnable {
+        self.rb.save(&self.tx_id, entity).await
+    }
+
+    pub async fn save_returning<T>(&self, entity: &T) -> Result<T, crate::core::Error> where T: CRUDEnable {
+        self.rb.save_returning(&self.tx_id, entity).await
+    }
+
+    pub async fn save_batch<T>(&self, entity: &[T]) -> Result<DBExecResult, crate::core::Error> where T: CRUDEnable {
+        self.rb.save_batch(&self.tx_id, entity).await
+    }
+
+    pub async fn save_or_update<T>(&self, entity: &T, conflict_columns: &[&str]) -> Result<DBExecResult, crate::core::Error> where T: CRUDEnable {
+        self.rb.save_or_update(&self.tx_id, entity, conflict_columns).await
+    }
+
+    pub async fn save_batch_or_update<T>(&self, entity: &[T], conflict_columns: &[&str]) -> Result<DBExecResult, crate::core::Error> where T: CRUDEnable {
+        self.rb.save_batch_or_update(&self.tx_id, entity, conflict_columns).await
+    }
+
+    pub async fn remove_by_wrapper<T>(&self, w: &Wrapper) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.remove_by_wrapper::<T>(&self.tx_id, w).await
+    }
+
+    pub async fn remove_by_id<T>(&self, id: &T::IdType) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.remove_by_id::<T>(&self.tx_id, id).await
+    }
+
+    pub async fn remove_batch_by_id<T>(&self, ids: &[T::IdType]) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.remove_batch_by_id::<T>(&self.tx_id, ids).await
+    }
+
+    pub async fn restore_by_wrapper<T>(&self, w: &Wrapper) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.restore_by_wrapper::<T>(&self.tx_id, w).await
+    }
+
+    pub async fn restore_by_id<T>(&self, id: &T::IdType) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.restore_by_id::<T>(&self.tx_id, id).await
+    }
+
+    pub async fn update_by_wrapper<T>(&self, arg: &T, w: &Wrapper, update_null_value: bool) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.update_by_wrapper(&self.tx_id, arg, w, update_null_value).await
+    }
+
+    pub async fn update_by_id<T>(&self, arg: &T) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.update_by_id(&self.tx_id, arg).await
+    }
+
+    pub async fn update_columns_by_id<T>(&self, arg: &T, columns: &[&str]) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.update_columns_by_id(&self.tx_id, arg, columns).await
+    }
+
+    pub async fn update_batch_by_id<T>(&self, args: &[T]) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.update_batch_by_id(&self.tx_id, args).await
+    }
+
+    pub async fn update_by_set_wrapper<T>(&self, set: &UpdateWrapper, w: &Wrapper) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.update_by_set_wrapper::<T>(&self.tx_id, set, w).await
+    }
+
+    pub async fn fetch_by_wrapper<T>(&self, w: &Wrapper) -> Result<T, crate::core::Error> where T: CRUDEnable {
+        self.rb.fetch_by_wrapper(&self.tx_id, w).await
+    }
+
+    pub async fn fetch_by_id<T>(&self, id: &T::IdType) -> Result<T, crate::core::Error> where T: CRUDEnable {
+        self.rb.fetch_by_id::<T>(&self.tx_id, id).await
+    }
+
+    pub async fn fetch_page_by_wrapper<T>(&self, w: &Wrapper, page: &dyn IPageRequest) -> Result<Page<T>, crate::core::Error> where T: CRUDEnable {
+        self.rb.fetch_page_by_wrapper(&self.tx_id, w, page).await
+    }
+
+    pub async fn count_by_wrapper<T>(&self, w: &Wrapper) -> Result<u64, crate::core::Error> where T: CRUDEnable {
+        self.rb.count_by_wrapper::<T>(&self.tx_id, w).await
+    }
+
+    pub async fn exists_by_wrapper<T>(&self, w: &Wrapper) -> Result<bool, crate::core::Error> where T: CRUDEnable {
+        self.rb.exists_by_wrapper::<T>(&self.tx_id, w).await
+    }
+
+    pub async fn sum_by_wrapper<T, R>(&self, column: &str, w: &Wrapper) -> Result<R, crate::core::Error> where T: CRUDEnable, R: DeserializeOwned + Send + Sync {
+        self.rb.sum_by_wrapper::<T, R>(&self.tx_id, column, w).await
+    }
+
+    pub async fn min_by_wrapper<T, R>(&self, column: &str, w: &Wrapper) -> Result<R, crate::core::Error> where T: CRUDEnable, R: DeserializeOwned + Send + Sync {
+        self.rb.min_by_wrapper::<T, R>(&self.tx_id, column, w).await
+    }
+
+    pub async fn max_by_wrapper<T, R>(&self, column: &str, w: &Wrapper) -> Result<R, crate::core::Error> where T: CRUDEnable, R: DeserializeOwned + Send + Sync {
+        self.rb.max_by_wrapper::<T, R>(&self.tx_id, column, w).await
+    }
+
+    pub async fn avg_by_wrapper<T, R>(&self, column: &str, w: &Wrapper) -> Result<R, crate::core::Error> where T: CRUDEnable, R: DeserializeOwned + Send + Sync {
+        self.rb.avg_by_wrapper::<T, R>(&self.tx_id, column, w).await
+    }
+
+    pub async fn list<T>(&self) -> Result<Vec<T>, crate::core::Error> where T: CRUDEnable {
+        self.rb.list(&self.tx_id).await
+    }
+
+    pub async fn list_by_wrapper<T>(&self, w: &Wrapper) -> Result<Vec<T>, crate::core::Error> where T: CRUDEnable {
+        self.rb.list_by_wrapper(&self.tx_id, w).await
+    }
+
+    pub async fn list_by_wrapper_columns<T, R>(&self, columns: &[&str], w: &Wrapper) -> Result<Vec<R>, crate::core::Error> where T: CRUDEnable, R: DeserializeOwned + Send + Sync {
+        self.rb.list_by_wrapper_columns::<T, R>(&self.tx_id, columns, w).await
+    }
+
+    pub async fn list_by_ids<T>(&self, ids: &[T::IdType]) -> Result<Vec<T>, crate::core::Error> where T: CRUDEnable {
+        self.rb.list_by_ids::<T>(&self.tx_id, ids).await
+    }
+
+    pub async fn load_children<P, C, F>(&self, parents: &mut [P], foreign_key: &str, set: F) -> Result<(), crate::core::Error>
+        where P: CRUDEnable, C: CRUDEnable, F: Fn(&mut P, Vec<C>) + Send + Sync {
+        self.rb.load_children::<P, C, F>(&self.tx_id, parents, foreign_key, set).await
+    }
+
+    pub async fn load_parent<C, P, F>(&self, children: &mut [C], foreign_key: &str, set: F) -> Result<(), crate::core::Error>
+        where C: CRUDEnable, P: CRUDEnable + Clone, F: Fn(&mut C, Option<P>) + Send + Sync {
+        self.rb.load_parent::<C, P, F>(&self.tx_id, children, foreign_key, set).await
+    }
+}
+
+impl<'a> Drop for TxGuard<'a> {
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        if self.rb.log_plugin.is_enable() {
+            self.rb.log_plugin.do_log(&format!("[rbatis] [{}] Rollback,because TxGuard drop without commit", self.tx_id));
+        }
+        let manager = self.rb.tx_manager.clone();
+        let tx_id = self.tx_id.clone();
+        crate::core::runtime::spawn(async move {
+            if let Err(e) = manager.rollback(&tx_id).await {
+                if manager.is_enable_log() {
+                    manager.do_log(&format!("[rbatis] [{}] Rollback fail:{}", tx_id, e));
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::plugin::log::RbatisLog;
+
+    #[derive(Default)]
+    struct TestTxEventPlugin {
+        events: Mutex<Vec<(String, TxEventKind, Option<String>, bool)>>,
+    }
+
+    impl TxEventPlugin for Arc<TestTxEventPlugin> {
+        fn on_event(&self, event: &TxEvent) {
+            self.events.lock().unwrap().push((event.tx_id.to_string(), event.kind, event.savepoint.map(|s| s.to_string()), event.success));
+        }
+    }
+
+    #[test]
+    fn test_tx_state_timeout() {
+        let state = TxState::new(Duration::from_millis(0));
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(state.is_timeout());
+        assert!(!TxState::new(Duration::from_secs(60)).is_timeout());
+    }
+
+    #[test]
+    fn test_fire_event() {
+        let plugin = Arc::new(TestTxEventPlugin::default());
+        let manager = TxManager::new_arc(Arc::new(Box::new(RbatisLog::default()) as Box<dyn LogPlugin>),
+                                         Some(Arc::new(Box::new(plugin.clone()) as Box<dyn TxEventPlugin>)),
+                                         Duration::from_secs(60), Duration::from_secs(1));
+        assert_eq!(manager.tx_timeout(&TxOptions::new()), Duration::from_secs(60));
+        assert_eq!(manager.tx_timeout(&TxOptions::new().timeout(Duration::from_secs(1))), Duration::from_secs(1));
+
+        let state = TxState::new(manager.tx_timeout(&TxOptions::new()));
+        manager.fire_event("tx:1", TxEventKind::Begin, &state, None, true);
+        manager.fire_event("tx:1", TxEventKind::Rollback, &state, Some("rbatis_sp_1"), true);
+        manager.fire_event("tx:1", TxEventKind::Commit, &state, None, false);
+        let events = plugin.events.lock().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], ("tx:1".to_string(), TxEventKind::Begin, None, true));
+        assert_eq!(events[1], ("tx:1".to_string(), TxEventKind::Rollback, Some("rbatis_sp_1".to_string()), true));
+        assert_eq!(events[2], ("tx:1".to_string(), TxEventKind::Commit, None, false));
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::convert::StmtConvert;
+use crate::core::db::DriverType;
+use crate::core::Error;
+use crate::sql::ident::{is_safe_sql_fragment, is_valid_ident};
+
+/// the SET part of update sql, use CRUD::update_by_set_wrapper() to update without an entity
+///
+/// for Example:
+///         let set = UpdateWrapper::new(&DriverType::Mysql)
+///             .set_sql("counter", "counter + 1")
+///             .set("status", 1)
+///             .check().unwrap();
+///         rb.update_by_set_wrapper::<BizActivity>("", &set, &Wrapper::new(&DriverType::Mysql).eq("id", "1")).await;
+///         //sql: UPDATE biz_activity SET counter = counter + 1, status = ? WHERE id = ?
+///
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpdateWrapper {
+    pub driver_type: DriverType,
+    pub sql: String,
+    pub args: Vec<Value>,
+    pub error: Option<Error>,
+}
+
+impl UpdateWrapper {
+    pub fn new(driver_type: &DriverType) -> Self {
+        Self {
+            driver_type: driver_type.clone(),
+            sql: "".to_string(),
+            args: vec![],
+            error: None,
+        }
+    }
+
+    /// check is done？and return cloned UpdateWrapper
+    pub fn check(&mut self) -> Result<UpdateWrapper, Error> {
+        if self.error.is_some() {
+            return Err(self.error.take().unwrap());
+        }
+        self.sql = self.sql.trim().trim_end_matches(",").to_string();
+        return Ok(self.clone());
+    }
+
+    /// set column = value
+    /// for example:
+    ///  set("status",1) " status = ? "
+    pub fn set<T>(&mut self, column: &str, obj: T) -> &mut Self
+        where T: Serialize {
+        if !self.check_column(column) {
+            return self;
+        }
+        let value_sql = self.driver_type.stmt_convert(self.args.len());
+        self.push_set(column, &value_sql);
+        self.args.push(json!(obj));
+        self
+    }
+
+    /// set column = sql expression
+    /// for example:
+    ///  set_sql("counter","counter + 1") " counter = counter + 1 "
+    pub fn set_sql(&mut self, column: &str, sql: &str) -> &mut Self {
+        if !self.check_column(column) {
+            return self;
+        }
+        if !is_safe_sql_fragment(sql) {
+            if self.error.is_none() {
+                self.error = Some(Error::from(format!("[rbatis] update wrapper set sql: '{}' is not safe!", sql)));
+            }
+            return self;
+        }
+        self.push_set(column, sql);
+        self
+    }
+
+    /// set column = NULL
+    pub fn set_null(&mut self, column: &str) -> &mut Self {
+        self.set_sql(column, "NULL")
+    }
+
+    fn push_set(&mut self, column: &str, value_sql: &str) {
+        if !self.sql.is_empty() {
+            self.sql.push_str(", ");
+        }
+        self.sql.push_str(format!("{} = {}", column, value_sql).as_str());
+    }
+
+    fn check_column(&mut self, column: &str) -> bool {
+        if !is_valid_ident(column) {
+            if self.error.is_none() {
+                self.error = Some(Error::from(format!("[rbatis] update wrapper column: '{}' is invalid!", column)));
+            }
+            return false;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::core::db::DriverType;
+    use crate::update_wrapper::UpdateWrapper;
+
+    #[test]
+    fn test_set() {
+        let w = UpdateWrapper::new(&DriverType::Postgres)
+            .set_sql("counter", "counter + 1")
+            .set("status", 1)
+            .set("name", "a")
+            .check().unwrap();
+        assert_eq!(w.sql, "counter = counter + 1, status = $1, name = $2");
+        assert_eq!(w.args, vec![json!(1), json!("a")]);
+
+        let w = UpdateWrapper::new(&DriverType::Mysql)
+            .set_sql("counter", "0; drop table biz_activity")
+            .check();
+        assert!(w.is_err());
+    }
+}
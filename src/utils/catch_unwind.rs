@@ -0,0 +1,41 @@
+use std::any::Any;
+use std::future::Future;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// the future catch panic when poll the inner future,return Err(panic payload) if panic
+pub struct CatchUnwind<F> where F: Future {
+    inner: Pin<Box<F>>,
+}
+
+impl<F> CatchUnwind<F> where F: Future {
+    pub fn new(f: F) -> Self {
+        Self {
+            inner: Box::pin(f)
+        }
+    }
+}
+
+impl<F> Future for CatchUnwind<F> where F: Future {
+    type Output = Result<F::Output, Box<dyn Any + Send>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &mut self.inner;
+        match catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(v)) => Poll::Ready(Ok(v)),
+            Err(e) => Poll::Ready(Err(e))
+        }
+    }
+}
+
+#[test]
+fn test_catch_unwind() {
+    let r = crate::core::runtime::block_on(CatchUnwind::new(async { 1 }));
+    assert_eq!(r.unwrap(), 1);
+    let r = crate::core::runtime::block_on(CatchUnwind::new(async {
+        panic!("test panic");
+    }));
+    assert!(r.is_err());
+}
@@ -5,4 +5,5 @@ pub mod value_util;
 pub mod bencher;
 pub mod join_in;
 pub mod error_util;
-pub mod array_util;
\ No newline at end of file
+pub mod array_util;
+pub mod catch_unwind;
@@ -1,3 +1,5 @@
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
 use std::ops::Add;
 
 use serde::{Deserialize, Serialize};
@@ -6,6 +8,9 @@ use serde_json::{json, Map, Value};
 use crate::core::convert::StmtConvert;
 use crate::core::db::DriverType;
 use crate::core::Error;
+use crate::crud::CRUDEnable;
+use crate::sql::ident::{is_safe_sql_fragment, is_valid_ident};
+use crate::sql::{DebugSql, IdentQuote, PageLimit};
 
 /// you can serialize to JSON, and Clone, Debug
 /// use json rpc send this Wrapper to server
@@ -38,6 +43,15 @@ pub struct Wrapper {
     pub args: Vec<serde_json::Value>,
     pub error: Option<Error>,
     pub checked: bool,
+    /// if true, CRUD will not add the logic delete filter
+    #[serde(default)]
+    pub with_deleted: bool,
+    /// if true, the column will be quoted. for example: mysql `name`,postgres "name",mssql [name]
+    #[serde(default)]
+    pub quote_columns: bool,
+    /// if not empty, only these columns can be used
+    #[serde(default)]
+    pub allow_columns: Vec<String>,
 }
 
 impl Wrapper {
@@ -48,9 +62,21 @@ impl Wrapper {
             args: vec![],
             error: None,
             checked: false,
+            with_deleted: false,
+            quote_columns: false,
+            allow_columns: vec![],
         }
     }
 
+    /// new empty Wrapper with the settings(with_deleted,quote_columns,allow_columns) of this Wrapper
+    pub fn new_child(&self) -> Self {
+        let mut child = Wrapper::new(&self.driver_type);
+        child.with_deleted = self.with_deleted;
+        child.quote_columns = self.quote_columns;
+        child.allow_columns = self.allow_columns.clone();
+        child
+    }
+
     pub fn from(driver_type: &DriverType, sql: &str, args: &Vec<serde_json::Value>) -> Self {
         Self {
             driver_type: driver_type.clone(),
@@ -58,9 +84,18 @@ impl Wrapper {
             args: args.clone(),
             error: None,
             checked: false,
+            with_deleted: false,
+            quote_columns: false,
+            allow_columns: vec![],
         }
     }
 
+    /// debug only! return sql with inlined args, for example: "id = ? AND name = ?" => "id = 1 AND name = 'a'"
+    /// do not execute the debug sql,use sql and args
+    pub fn to_debug_sql(&self) -> String {
+        self.driver_type.debug_sql(&self.sql, &self.args)
+    }
+
     //check is done？and return cloned Wrapper
     pub fn check(&mut self) -> Result<Wrapper, Error> {
         if self.error.is_some() {
@@ -73,6 +108,57 @@ impl Wrapper {
         return Ok(self.clone());
     }
 
+    /// the CRUD methods will not filter the logic deleted rows
+    /// for Example:
+    /// rb.list_by_wrapper::<BizActivity>("", &Wrapper::new(&DriverType::Mysql).with_deleted()).await;
+    /// [rbatis] Query ==> SELECT * FROM biz_activity
+    pub fn with_deleted(&mut self) -> &mut Self {
+        self.with_deleted = true;
+        self
+    }
+
+    /// quote the column of this Wrapper
+    /// for Example:
+    /// Wrapper::new(&DriverType::Mysql).quote_columns().eq("name", "a") //sql: `name` = ?
+    pub fn quote_columns(&mut self) -> &mut Self {
+        self.quote_columns = true;
+        self
+    }
+
+    /// only these columns can be used,other column will set the wrapper error.
+    /// you should set it before add the conditions, it is useful when column is from user input(for example order by)
+    pub fn allow_columns(&mut self, columns: &[&str]) -> &mut Self {
+        self.allow_columns = columns.iter().map(|x| x.trim().to_string()).collect();
+        self
+    }
+
+    /// only T::table_columns() can be used
+    pub fn allow_table_columns<T>(&mut self) -> &mut Self where T: CRUDEnable {
+        let columns = T::table_columns();
+        let columns: Vec<&str> = columns.split(",").collect();
+        self.allow_columns(&columns)
+    }
+
+    /// check the column and return column sql. if the column is invalid, set the error
+    fn column_sql(&mut self, column: &str) -> String {
+        if !is_valid_ident(column) {
+            if self.error.is_none() {
+                self.error = Some(Error::from(format!("[rbatis] wrapper column: '{}' is invalid!", column)));
+            }
+            return column.to_string();
+        }
+        if !self.allow_columns.is_empty() && !self.allow_columns.iter().any(|x| x.eq(column)) {
+            if self.error.is_none() {
+                self.error = Some(Error::from(format!("[rbatis] wrapper column: '{}' is not allowed!", column)));
+            }
+            return column.to_string();
+        }
+        if self.quote_columns {
+            return self.driver_type.quote_ident(column);
+        }
+        column.to_string()
+    }
+
     /// link left Wrapper to this Wrapper
     /// for Example:
     /// let w = Wrapper::new(&DriverType::Postgres).push_sql("(").eq("a", "1").push_sql(")").check().unwrap();
@@ -92,15 +178,7 @@ impl Wrapper {
         where T: Serialize {
         let mut new_sql = sql.to_string();
         if self.driver_type.is_number_type() {
-            let self_arg_len = self.args.len();
-            for index in 0..args.len() {
-                let str = self.driver_type.stmt_convert(index);
-                new_sql = new_sql.replace(str.as_str(), self.driver_type.stmt_convert(index + args.len()).as_str());
-            }
-            for index in args.len()..self_arg_len {
-                let str = self.driver_type.stmt_convert(index);
-                new_sql = new_sql.replace(str.as_str(), self.driver_type.stmt_convert(index + args.len()).as_str());
-            }
+            new_sql = renumber_placeholder(&new_sql, self.args.len());
         }
         self.sql.push_str(new_sql.as_str());
 
@@ -116,6 +194,52 @@ impl Wrapper {
     }
 
 
+    /// push ( child conditions ) with AND, if the child have no sql, do nothing
+    /// for Example:
+    ///  let w = Wrapper::new(&DriverType::Postgres)
+    ///             .eq("a", 1)
+    ///             .and_nested(|w| w.eq("b", 2).or().eq("c", 3))
+    ///             .check().unwrap();
+    ///  //sql: a = $1 AND (b = $2 OR c = $3)
+    pub fn and_nested<F>(&mut self, f: F) -> &mut Self
+        where F: FnOnce(&mut Wrapper) -> &mut Wrapper {
+        self.push_nested(true, f)
+    }
+
+    /// push ( child conditions ) with OR, if the child have no sql, do nothing
+    /// for Example:
+    ///  let w = Wrapper::new(&DriverType::Postgres)
+    ///             .eq("a", 1)
+    ///             .or_nested(|w| w.eq("b", 2).eq("c", 3))
+    ///             .check().unwrap();
+    ///  //sql: a = $1 OR (b = $2 AND c = $3)
+    pub fn or_nested<F>(&mut self, f: F) -> &mut Self
+        where F: FnOnce(&mut Wrapper) -> &mut Wrapper {
+        self.push_nested(false, f)
+    }
+
+    fn push_nested<F>(&mut self, is_and: bool, f: F) -> &mut Self
+        where F: FnOnce(&mut Wrapper) -> &mut Wrapper {
+        let mut child = self.new_child();
+        f(&mut child);
+        if child.error.is_some() {
+            self.error = child.error.take();
+            return self;
+        }
+        child.trim_and();
+        child.trim_or();
+        let child_sql = child.sql.trim().to_string();
+        if child_sql.is_empty() {
+            return self;
+        }
+        if is_and {
+            self.and();
+        } else {
+            self.or();
+        }
+        self.push_sql("(").push(&child_sql, &child.args).push_sql(")")
+    }
+
     /// do method,if test is true
     /// for example:
     ///  let arg = 1;
@@ -218,6 +342,12 @@ impl Wrapper {
     }
 
     pub fn having(&mut self, sql_having: &str) -> &mut Self {
+        if !is_safe_sql_fragment(sql_having) {
+            if self.error.is_none() {
+                self.error = Some(Error::from(format!("[rbatis] wrapper having sql: '{}' is not safe!", sql_having)));
+            }
+            return self;
+        }
         self.and();
         self.sql.push_str(format!(" HAVING {} ", sql_having).as_str());
         self
@@ -259,7 +389,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" = {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(v);
 
@@ -272,7 +403,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" <> {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(v);
         self
@@ -289,6 +421,7 @@ impl Wrapper {
             .trim_end_matches("OR").to_string();
         self.sql.push_str(" ORDER BY ");
         for x in columns {
+            let x = self.column_sql(x);
             if is_asc {
                 self.sql.push_str(format!("{} ASC", x).as_str());
             } else {
@@ -314,7 +447,8 @@ impl Wrapper {
             .trim_end_matches("OR").to_string();
         self.sql.push_str(" GROUP BY ");
         for x in columns {
-            self.sql.push_str(x);
+            let x = self.column_sql(x);
+            self.sql.push_str(&x);
             if (index + 1) != len {
                 self.sql.push_str(" , ");
                 index += 1;
@@ -328,7 +462,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" > {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(v);
         self
@@ -338,7 +473,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" >= {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(v);
         self
@@ -349,7 +485,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" < {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(v);
 
@@ -361,7 +498,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" <= {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(v);
         self
@@ -372,7 +510,8 @@ impl Wrapper {
         self.and();
         let min_v = json!(min);
         let max_v = json!(max);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" BETWEEN {} AND {}", self.driver_type.stmt_convert(self.args.len()), self.driver_type.stmt_convert(self.args.len() + 1)).as_str());
         self.args.push(min_v);
         self.args.push(max_v);
@@ -384,7 +523,8 @@ impl Wrapper {
         self.and();
         let min_v = json!(min);
         let max_v = json!(max);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" NOT BETWEEN {} AND {}", self.driver_type.stmt_convert(self.args.len()), self.driver_type.stmt_convert(self.args.len() + 1)).as_str());
         self.args.push(min_v);
         self.args.push(max_v);
@@ -401,7 +541,8 @@ impl Wrapper {
         } else {
             v_str = format!("%{}%", v.to_string());
         }
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" LIKE {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(json!(v_str));
         self
@@ -417,7 +558,8 @@ impl Wrapper {
         } else {
             v_str = format!("%{}", v.to_string());
         }
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" LIKE {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(json!(v_str));
         self
@@ -433,7 +575,8 @@ impl Wrapper {
         } else {
             v_str = format!("{}%", v.to_string());
         }
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" LIKE {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(json!(v_str));
         self
@@ -449,22 +592,131 @@ impl Wrapper {
         } else {
             v_str = format!("%{}%", v.to_string());
         }
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(format!(" NOT LIKE {}", self.driver_type.stmt_convert(self.args.len())).as_str());
         self.args.push(json!(v_str));
         self
     }
 
+    /// case-insensitive like
+    /// for example:
+    ///  postgres: ilike("name","a") " name ILIKE $1 " arg: "%a%"
+    ///  other: ilike("name","a") " LOWER(name) LIKE LOWER(?) " arg: "%a%"
+    pub fn ilike<T>(&mut self, column: &str, obj: T) -> &mut Self
+        where T: Serialize {
+        self.and();
+        let v = json!(obj);
+        let v_str = match v.as_str() {
+            Some(s) => format!("%{}%", s),
+            None => format!("%{}%", v.to_string())
+        };
+        let column = self.column_sql(column);
+        let value_sql = self.driver_type.stmt_convert(self.args.len());
+        match self.driver_type {
+            DriverType::Postgres => {
+                self.sql.push_str(format!("{} ILIKE {}", column, value_sql).as_str());
+            }
+            _ => {
+                self.sql.push_str(format!("LOWER({}) LIKE LOWER({})", column, value_sql).as_str());
+            }
+        }
+        self.args.push(json!(v_str));
+        self
+    }
+
+    /// json column path value equal, path must like "$.a.b[0]"
+    /// for example:
+    ///  mysql/sqlite: json_extract_eq("attrs","$.color","red") " JSON_EXTRACT(attrs, '$.color') = ? "
+    ///  postgres: json_extract_eq("attrs","$.color","red") " attrs->>'color' = $1 ",the value is compared as text
+    ///  mssql: json_extract_eq("attrs","$.color","red") " JSON_VALUE(attrs, '$.color') = ? "
+    pub fn json_extract_eq<T>(&mut self, column: &str, path: &str, obj: T) -> &mut Self
+        where T: Serialize {
+        let segments = match parse_json_path(path) {
+            Some(segments) => segments,
+            None => {
+                if self.error.is_none() {
+                    self.error = Some(Error::from(format!("[rbatis] wrapper json path: '{}' is invalid!", path)));
+                }
+                return self;
+            }
+        };
+        self.and();
+        let mut v = json!(obj);
+        let column = self.column_sql(column);
+        let value_sql = self.driver_type.stmt_convert(self.args.len());
+        match self.driver_type {
+            DriverType::Postgres => {
+                let mut path_sql = String::new();
+                let len = segments.len();
+                for (index, segment) in segments.iter().enumerate() {
+                    if index + 1 == len {
+                        path_sql.push_str("->>");
+                    } else {
+                        path_sql.push_str("->");
+                    }
+                    path_sql.push_str(segment);
+                }
+                if len == 0 {
+                    path_sql.push_str("#>>'{}'");
+                }
+                self.sql.push_str(format!("{}{} = {}", column, path_sql, value_sql).as_str());
+                //->> return text
+                if !v.is_string() && !v.is_null() {
+                    v = json!(v.to_string());
+                }
+            }
+            DriverType::Mssql => {
+                self.sql.push_str(format!("JSON_VALUE({}, '{}') = {}", column, path, value_sql).as_str());
+            }
+            _ => {
+                self.sql.push_str(format!("JSON_EXTRACT({}, '{}') = {}", column, path, value_sql).as_str());
+            }
+        }
+        self.args.push(v);
+        self
+    }
+
+    /// json column contains the json value
+    /// for example:
+    ///  mysql: json_contains("tags",json!(["a"])) " JSON_CONTAINS(tags, ?) " arg: "[\"a\"]"
+    ///  postgres: json_contains("tags",json!(["a"])) " tags @> CAST($1 AS jsonb) " arg: "[\"a\"]"
+    ///  sqlite/mssql: not support
+    pub fn json_contains<T>(&mut self, column: &str, obj: T) -> &mut Self
+        where T: Serialize {
+        let value_sql = self.driver_type.stmt_convert(self.args.len());
+        let sql = match self.driver_type {
+            DriverType::Mysql => {
+                format!("JSON_CONTAINS({}, {})", self.column_sql(column), value_sql)
+            }
+            DriverType::Postgres => {
+                format!("{} @> CAST({} AS jsonb)", self.column_sql(column), value_sql)
+            }
+            _ => {
+                if self.error.is_none() {
+                    self.error = Some(Error::from(format!("[rbatis] wrapper json_contains not support DriverType:{:?}", self.driver_type)));
+                }
+                return self;
+            }
+        };
+        self.and();
+        self.sql.push_str(sql.as_str());
+        self.args.push(json!(json!(obj).to_string()));
+        self
+    }
+
     pub fn is_null(&mut self, column: &str) -> &mut Self {
         self.and();
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(" IS NULL");
         self
     }
 
     pub fn is_not_null(&mut self, column: &str) -> &mut Self {
         self.and();
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         self.sql.push_str(" IS NOT NULL");
         self
     }
@@ -477,7 +729,8 @@ impl Wrapper {
             return self;
         }
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         let vec = v.as_array().unwrap();
         let mut sqls = String::new();
         for x in vec {
@@ -507,7 +760,8 @@ impl Wrapper {
         where T: Serialize {
         self.and();
         let v = json!(obj);
-        self.sql.push_str(column);
+        let column = self.column_sql(column);
+        self.sql.push_str(&column);
         let vec = v.as_array().unwrap();
         let mut sqls = String::new();
         for x in vec {
@@ -520,6 +774,159 @@ impl Wrapper {
         self
     }
 
+    /// typed equal, the column is generated by CRUDEnable macro
+    /// for example:
+    ///  eq_col(&BizActivity::COLUMNS.name, "test") " name = ? "
+    pub fn eq_col<T, V>(&mut self, column: &Column<T>, obj: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.eq(column.name, obj.into())
+    }
+
+    /// typed not equal
+    pub fn ne_col<T, V>(&mut self, column: &Column<T>, obj: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.ne(column.name, obj.into())
+    }
+
+    /// typed column > obj
+    pub fn gt_col<T, V>(&mut self, column: &Column<T>, obj: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.gt(column.name, obj.into())
+    }
+
+    /// typed column >= obj
+    pub fn ge_col<T, V>(&mut self, column: &Column<T>, obj: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.ge(column.name, obj.into())
+    }
+
+    /// typed column < obj
+    pub fn lt_col<T, V>(&mut self, column: &Column<T>, obj: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.lt(column.name, obj.into())
+    }
+
+    /// typed column <= obj
+    pub fn le_col<T, V>(&mut self, column: &Column<T>, obj: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.le(column.name, obj.into())
+    }
+
+    /// typed column between min and max
+    pub fn between_col<T, V>(&mut self, column: &Column<T>, min: V, max: V) -> &mut Self
+        where T: Serialize, V: Into<T> {
+        self.between(column.name, min.into(), max.into())
+    }
+
+    /// typed like, only for String column
+    pub fn like_col<V>(&mut self, column: &Column<String>, obj: V) -> &mut Self
+        where V: Into<String> {
+        self.like(column.name, obj.into())
+    }
+
+    /// typed column in (*,*,*)
+    pub fn in_col<T>(&mut self, column: &Column<T>, obj: &[T]) -> &mut Self
+        where T: Serialize {
+        self.in_array(column.name, obj)
+    }
+
+    /// typed column not in (*,*,*)
+    pub fn not_in_col<T>(&mut self, column: &Column<T>, obj: &[T]) -> &mut Self
+        where T: Serialize {
+        self.not_in(column.name, obj)
+    }
+
+    pub fn is_null_col<T>(&mut self, column: &Column<T>) -> &mut Self {
+        self.is_null(column.name)
+    }
+
+    pub fn is_not_null_col<T>(&mut self, column: &Column<T>) -> &mut Self {
+        self.is_not_null(column.name)
+    }
+
+    pub fn order_by_col<T>(&mut self, is_asc: bool, column: &Column<T>) -> &mut Self {
+        self.order_by(is_asc, &[column.name])
+    }
+
+    /// limit size, the sql is created by DriverType::page_limit_sql()
+    /// for example:
+    ///  mysql: limit(10) " LIMIT 0,10 "
+    ///  postgres/sqlite: limit(10) " LIMIT 10 OFFSET 0 "
+    ///  mssql: limit(10) " OFFSET 0 ROWS FETCH NEXT 10 ROWS ONLY ",mssql must use order_by before limit
+    pub fn limit(&mut self, size: u64) -> &mut Self {
+        self.limit_offset(0, size)
+    }
+
+    /// limit size and skip offset rows
+    pub fn limit_offset(&mut self, offset: u64, size: u64) -> &mut Self {
+        match self.driver_type.page_limit_sql(offset, size) {
+            Ok(limit_sql) => {
+                self.sql = self.sql.trim()
+                    .trim_end_matches("WHERE")
+                    .trim_end_matches("AND")
+                    .trim_end_matches("OR").to_string();
+                self.sql.push_str(limit_sql.as_str());
+            }
+            Err(e) => {
+                if self.error.is_none() {
+                    self.error = Some(e);
+                }
+            }
+        }
+        self
+    }
+
+    /// gen sql: EXISTS (sub query),the sub wrapper args will push into self
+    /// for example:
+    ///  let sub = Wrapper::new(&DriverType::Postgres).push_sql("SELECT 1 FROM biz_activity_img WHERE activity_id = biz_activity.id AND ").eq("status", 1).check().unwrap();
+    ///  Wrapper::new(&DriverType::Postgres).eq("name", "a").exists(&sub)
+    ///  //sql: name = $1 AND EXISTS (SELECT 1 FROM biz_activity_img WHERE activity_id = biz_activity.id AND status = $2)
+    pub fn exists(&mut self, sub: &Wrapper) -> &mut Self {
+        self.push_sub_query(None, "EXISTS", sub)
+    }
+
+    /// gen sql: NOT EXISTS (sub query)
+    pub fn not_exists(&mut self, sub: &Wrapper) -> &mut Self {
+        self.push_sub_query(None, "NOT EXISTS", sub)
+    }
+
+    /// gen sql: column IN (sub query)
+    /// for example:
+    ///  let sub = Wrapper::new(&DriverType::Mysql).push_sql("SELECT activity_id FROM biz_activity_img WHERE ").eq("status", 1).check().unwrap();
+    ///  Wrapper::new(&DriverType::Mysql).in_sub("id", &sub)
+    ///  //sql: id IN (SELECT activity_id FROM biz_activity_img WHERE status = ?)
+    pub fn in_sub(&mut self, column: &str, sub: &Wrapper) -> &mut Self {
+        self.push_sub_query(Some(column), "IN", sub)
+    }
+
+    /// gen sql: column NOT IN (sub query)
+    pub fn not_in_sub(&mut self, column: &str, sub: &Wrapper) -> &mut Self {
+        self.push_sub_query(Some(column), "NOT IN", sub)
+    }
+
+    fn push_sub_query(&mut self, column: Option<&str>, opt: &str, sub: &Wrapper) -> &mut Self {
+        let sub = match sub.clone().check() {
+            Ok(sub) => sub,
+            Err(e) => {
+                if self.error.is_none() {
+                    self.error = Some(e);
+                }
+                return self;
+            }
+        };
+        self.and();
+        if let Some(column) = column {
+            let column = self.column_sql(column);
+            self.sql.push_str(&column);
+            self.sql.push_str(" ");
+        }
+        self.sql.push_str(opt);
+        self.sql.push_str(" (");
+        self.push(sub.sql.trim(), &sub.args);
+        self.sql.push_str(")");
+        self
+    }
+
     pub fn trim_and(&mut self) -> &mut Self {
         self.sql = self.sql.trim()
             .trim_start_matches("AND")
@@ -554,6 +961,126 @@ impl Case {
     }
 }
 
+/// the table column with value type T(Option<T> field is T),
+/// the CRUDEnable macro will generate all columns of struct, for Example:
+///
+/// #[crud_enable]
+/// pub struct BizActivity { pub id: Option<String>, pub name: Option<String>, pub status: Option<i32> }
+///
+/// let c = BizActivity::COLUMNS;
+/// let w = Wrapper::new(&DriverType::Mysql).eq_col(&c.name, "test").gt_col(&c.status, 1).check().unwrap();
+/// //sql: name = ? AND status > ?
+pub struct Column<T> {
+    pub name: &'static str,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Column<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Column<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.name)
+    }
+}
+
+impl<T> Copy for Column<T> {}
+
+impl<T> Debug for Column<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Column").field("name", &self.name).finish()
+    }
+}
+
+/// parse json path "$.a.b[0]" to postgres path segments ["'a'","'b'","0"], return None if path is invalid
+fn parse_json_path(path: &str) -> Option<Vec<String>> {
+    if !path.starts_with("$") {
+        return None;
+    }
+    let mut segments = vec![];
+    let mut chars = path[1..].chars().peekable();
+    while let Some(c) = chars.next() {
+        let mut segment = String::new();
+        match c {
+            '.' => {
+                while let Some(d) = chars.peek() {
+                    if !d.is_ascii_alphanumeric() && *d != '_' {
+                        break;
+                    }
+                    segment.push(*d);
+                    chars.next();
+                }
+                if segment.is_empty() {
+                    return None;
+                }
+                segments.push(format!("'{}'", segment));
+            }
+            '[' => {
+                while let Some(d) = chars.peek() {
+                    if !d.is_ascii_digit() {
+                        break;
+                    }
+                    segment.push(*d);
+                    chars.next();
+                }
+                if segment.is_empty() || chars.next() != Some(']') {
+                    return None;
+                }
+                segments.push(segment);
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+    Some(segments)
+}
+
+/// is the where sql start with a clause after WHERE(no condition before it),
+/// for example: "ORDER BY id", "LIMIT 0,10", "OFFSET 0 ROWS FETCH NEXT 10 ROWS ONLY"
+pub fn is_tail_clause(where_sql: &str) -> bool {
+    let where_sql = where_sql.trim_start();
+    where_sql.starts_with("ORDER BY")
+        || where_sql.starts_with("GROUP BY")
+        || where_sql.starts_with("LIMIT")
+        || where_sql.starts_with("OFFSET")
+}
+
+/// renumber the placeholder $n to $(n + offset) in one pass, for example: offset=3, "a = $1" => "a = $4"
+fn renumber_placeholder(sql: &str, offset: usize) -> String {
+    let mut new_sql = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        new_sql.push(c);
+        if c != '$' {
+            continue;
+        }
+        let mut num = String::new();
+        while let Some(d) = chars.peek() {
+            if !d.is_ascii_digit() {
+                break;
+            }
+            num.push(*d);
+            chars.next();
+        }
+        match num.parse::<usize>() {
+            Ok(n) => {
+                new_sql.push_str((n + offset).to_string().as_str());
+            }
+            Err(_) => {
+                new_sql.push_str(num.as_str());
+            }
+        }
+    }
+    new_sql
+}
+
 #[cfg(test)]
 mod test {
     use serde_json::json;
@@ -561,7 +1088,7 @@ mod test {
 
     use crate::core::db::DriverType;
     use crate::utils::bencher::QPS;
-    use crate::wrapper::{Case, Wrapper};
+    use crate::wrapper::{Case, Column, is_tail_clause, parse_json_path, renumber_placeholder, Wrapper};
 
     #[test]
     fn test_trim() {
@@ -706,4 +1233,143 @@ mod test {
         assert_eq!(w2.sql.contains("b = $1"), true);
         assert_eq!(w2.sql.contains("a = $4"), true);
     }
+
+    #[test]
+    fn test_renumber_placeholder() {
+        assert_eq!(renumber_placeholder("a = $1 AND b = $2", 9), "a = $10 AND b = $11");
+        assert_eq!(renumber_placeholder("a = $10 AND b = $1", 1), "a = $11 AND b = $2");
+        assert_eq!(renumber_placeholder("a = $ ", 1), "a = $ ");
+    }
+
+    #[test]
+    fn test_column() {
+        let name = Column::<String>::new("name");
+        let status = Column::<i32>::new("status");
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq_col(&name, "test")
+            .gt_col(&status, 1)
+            .in_col(&status, &[1, 2])
+            .order_by_col(false, &status)
+            .check().unwrap();
+        assert_eq!(w.sql, "name = $1 AND status > $2 AND status IN ( $3 , $4 ) ORDER BY status DESC");
+        assert_eq!(w.args, vec![json!("test"), json!(1), json!(1), json!(2)]);
+    }
+
+    #[test]
+    fn test_column_check() {
+        let w = Wrapper::new(&DriverType::Mysql)
+            .quote_columns()
+            .eq("name", "a")
+            .order_by(true, &["create_time"])
+            .check().unwrap();
+        assert_eq!(w.sql, "`name` = ? ORDER BY `create_time` ASC");
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .order_by(true, &["id;delete from biz_activity"])
+            .check();
+        assert!(w.is_err());
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .allow_columns(&["id", "name"])
+            .eq("name", "a")
+            .order_by(false, &["create_time"])
+            .check();
+        assert!(w.is_err());
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .group_by(&["id"])
+            .having("count(1) > 1; drop table biz_activity")
+            .check();
+        assert!(w.is_err());
+    }
+
+    #[test]
+    fn test_limit() {
+        let w = Wrapper::new(&DriverType::Mysql).eq("a", 1).limit(10).check().unwrap();
+        assert_eq!(w.sql, "a = ? LIMIT 0,10");
+        let w = Wrapper::new(&DriverType::Postgres).eq("a", 1).order_by(true, &["id"]).limit_offset(20, 10).check().unwrap();
+        assert_eq!(w.sql, "a = $1 ORDER BY id ASC LIMIT 10 OFFSET 20");
+        let w = Wrapper::new(&DriverType::Mysql).limit(10).check().unwrap();
+        assert!(is_tail_clause(&w.sql));
+        assert!(!is_tail_clause("a = ? LIMIT 0,10"));
+    }
+
+    #[test]
+    fn test_sub_query() {
+        let sub = Wrapper::new(&DriverType::Postgres)
+            .push_sql("SELECT 1 FROM biz_activity_img WHERE activity_id = biz_activity.id AND ")
+            .eq("status", 1)
+            .check().unwrap();
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("name", "a")
+            .exists(&sub)
+            .not_in_sub("id", &sub)
+            .check().unwrap();
+        assert_eq!(w.sql, "name = $1 AND EXISTS (SELECT 1 FROM biz_activity_img WHERE activity_id = biz_activity.id AND status = $2) AND id NOT IN (SELECT 1 FROM biz_activity_img WHERE activity_id = biz_activity.id AND status = $3)");
+        assert_eq!(w.args, vec![json!("a"), json!(1), json!(1)]);
+    }
+
+    #[test]
+    fn test_ilike_json() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .ilike("name", "a")
+            .json_extract_eq("attrs", "$.size[0]", 1)
+            .json_contains("tags", json!(["a"]))
+            .check().unwrap();
+        assert_eq!(w.sql, "name ILIKE $1 AND attrs->'size'->>0 = $2 AND tags @> CAST($3 AS jsonb)");
+        assert_eq!(w.args, vec![json!("%a%"), json!("1"), json!("[\"a\"]")]);
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .ilike("name", "a")
+            .json_extract_eq("attrs", "$.color", "red")
+            .check().unwrap();
+        assert_eq!(w.sql, "LOWER(name) LIKE LOWER(?) AND JSON_EXTRACT(attrs, '$.color') = ?");
+
+        let w = Wrapper::new(&DriverType::Mssql).json_extract_eq("attrs", "$.color", "red").check().unwrap();
+        assert_eq!(w.sql, "JSON_VALUE(attrs, '$.color') = ?");
+
+        assert!(Wrapper::new(&DriverType::Mysql).json_extract_eq("attrs", "$.a') OR 1=1 --", 1).check().is_err());
+        assert!(Wrapper::new(&DriverType::Sqlite).json_contains("tags", 1).check().is_err());
+        assert_eq!(parse_json_path("$"), Some(vec![]));
+        assert_eq!(parse_json_path("color"), None);
+    }
+
+    #[test]
+    fn test_to_debug_sql() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("id", 1)
+            .like("name", "a'")
+            .is_null("delete_time")
+            .check().unwrap();
+        assert_eq!(w.to_debug_sql(), "id = 1 AND name LIKE '%a''%' AND delete_time IS NULL");
+    }
+
+    #[test]
+    fn test_nested() {
+        let w = Wrapper::new(&DriverType::Postgres)
+            .eq("a", 1)
+            .and_nested(|w| w.eq("b", 2).or().eq("c", 3))
+            .or_nested(|w| w.eq("d", 4).eq("e", 5))
+            .and_nested(|w| w)
+            .check().unwrap();
+        assert_eq!(w.sql, "a = $1 AND (b = $2 OR c = $3) OR (d = $4 AND e = $5)");
+        assert_eq!(w.args, vec![json!(1), json!(2), json!(3), json!(4), json!(5)]);
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .and_nested(|w| w.eq("b", 2))
+            .check().unwrap();
+        assert_eq!(w.sql, "(b = ?)");
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .quote_columns()
+            .and_nested(|w| w.eq("b", 2))
+            .check().unwrap();
+        assert_eq!(w.sql, "(`b` = ?)");
+
+        let w = Wrapper::new(&DriverType::Mysql)
+            .allow_columns(&["b"])
+            .and_nested(|w| w.eq("b", 2).or_nested(|w| w.eq("password", "x")))
+            .check();
+        assert!(w.is_err());
+    }
 }
\ No newline at end of file